@@ -0,0 +1,565 @@
+// ============================================================================
+// APPROVAL BRIDGE
+// ============================================================================
+//
+// `bridge.ps1` asks for approval by appending a pending request to
+// `bridge.json` and polling it until the status changes. The backend keeps
+// the last known state of that queue, watches the file and pushes changes to
+// the GUI: `bridge-request` when a request starts waiting and
+// `bridge-resolved` when it is approved, rejected or withdrawn. Changes made
+// through the commands below are emitted right away, without waiting for the
+// watcher.
+//
+// The watcher subscribes to file system notifications for the directory
+// rather than the file itself, because writers replace `bridge.json` by
+// renaming over it, which would orphan a watch on the old file. Where no
+// notifications are available (e.g. inotify watches used up) it falls back
+// to comparing the file's modification time and size a few times a second.
+//
+// Writers coordinate through `bridge.json.lock`, an advisory lock file
+// created exclusively and removed by its owner when done, and replace
+// `bridge.json` by renaming a temporary file over it, so readers never see a
+// partial write.
+// `revision` is bumped on every write; a writer whose read is no longer the
+// latest revision gets a conflict and starts over from the current file.
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const REQUEST_EVENT: &str = "bridge-request";
+const RESOLVED_EVENT: &str = "bridge-resolved";
+
+/// Writes arrive as bursts of events; wait this long for the rest before
+/// reading the file, and again between reads of a half-written file
+const SETTLE: Duration = Duration::from_millis(50);
+const RELOAD_ATTEMPTS: usize = 5;
+/// Poll interval when file notifications are unavailable
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+const PENDING: &str = "pending";
+
+/// How long a writer waits for the lock before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+const LOCK_RETRY: Duration = Duration::from_millis(20);
+/// A lock file older than this was left behind by a writer that died
+const STALE_LOCK: Duration = Duration::from_secs(10);
+
+/// Read-modify-write attempts before a conflict is reported
+const MAX_CONFLICT_RETRIES: usize = 5;
+
+fn get_bridge_path() -> PathBuf {
+    crate::get_base_dir().join("bridge.json")
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct BridgeRequest {
+    id: String,
+    message: String,
+    status: String, // "pending", "approved", "rejected"
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct BridgeData {
+    requests: Vec<BridgeRequest>,
+    auto_approve: bool,
+    /// Incremented by every write; files from older versions start at 0
+    #[serde(default)]
+    revision: u64,
+}
+
+impl Default for BridgeData {
+    fn default() -> Self {
+        Self {
+            requests: vec![],
+            auto_approve: true,
+            revision: 0,
+        }
+    }
+}
+
+/// None while the file cannot be read or parsed, e.g. halfway through a
+/// write by `bridge.ps1`
+fn load_bridge_data() -> Option<BridgeData> {
+    load_bridge_file(&get_bridge_path())
+}
+
+fn load_bridge_file(bridge_path: &Path) -> Option<BridgeData> {
+    if !bridge_path.exists() {
+        return Some(BridgeData::default());
+    }
+    let content = fs::read_to_string(bridge_path).ok()?;
+    // Windows PowerShell may write a byte order mark
+    serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()
+}
+
+fn read_bridge_data() -> BridgeData {
+    load_bridge_data().unwrap_or_default()
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn age(path: &Path) -> Option<Duration> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()?.elapsed().ok()
+}
+
+/// Exclusive hold on `<file>.lock`, released on drop
+struct BridgeLock {
+    path: PathBuf,
+    /// Written into the lock file, to recognise it when releasing
+    token: String,
+}
+
+impl BridgeLock {
+    fn acquire(target: &Path) -> Result<Self, String> {
+        let path = sibling(target, ".lock");
+        let token = format!("{} {:x}", std::process::id(), fastrand::u64(..));
+        let started = SystemTime::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    if let Err(e) = file.write_all(token.as_bytes()) {
+                        drop(file);
+                        let _ = fs::remove_file(&path);
+                        return Err(format!("Failed to lock {}: {}", target.display(), e));
+                    }
+                    return Ok(Self { path, token });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if age(&path).is_some_and(|age| age > STALE_LOCK) {
+                        break_stale_lock(&path);
+                        continue;
+                    }
+                    if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                        return Err(format!("{} is locked by another writer", target.display()));
+                    }
+                    std::thread::sleep(LOCK_RETRY);
+                }
+                Err(e) => return Err(format!("Failed to lock {}: {}", target.display(), e)),
+            }
+        }
+    }
+}
+
+impl Drop for BridgeLock {
+    /// A writer that stalled past `STALE_LOCK` may have had its lock broken
+    /// and taken by another; that one is left alone
+    fn drop(&mut self) {
+        release_lock(&self.path, |claimed| fs::read_to_string(claimed).is_ok_and(|content| content == self.token));
+    }
+}
+
+/// Remove a lock left behind by a dead writer
+fn break_stale_lock(path: &Path) {
+    release_lock(path, |claimed| age(claimed).is_some_and(|age| age > STALE_LOCK));
+}
+
+/// Remove the lock at `path` if `is_expected` holds for it. It is first
+/// renamed to a name only this call knows, so the check and the removal see
+/// the same file: of two writers releasing the same lock only one gets it,
+/// and a lock that replaced it in between is handed back instead of deleted.
+fn release_lock(path: &Path, is_expected: impl FnOnce(&Path) -> bool) {
+    let claimed = sibling(path, &format!(".claimed-{}-{:x}", std::process::id(), fastrand::u64(..)));
+    if fs::rename(path, &claimed).is_err() {
+        return;
+    }
+    if !is_expected(&claimed) {
+        // Linking fails rather than overwrites if yet another lock exists
+        let _ = fs::hard_link(&claimed, path);
+    }
+    let _ = fs::remove_file(&claimed);
+}
+
+#[derive(Debug, PartialEq)]
+enum BridgeWriteError {
+    /// Someone else wrote since `expected_revision` was read
+    Conflict,
+    /// The file could not be replaced right now, e.g. on Windows while
+    /// `bridge.ps1` has it open
+    Busy(String),
+    Failed(String),
+}
+
+/// Replace the file with `data` if it is still at `data.revision`, under the
+/// lock and via rename. Returns what was written, with the revision bumped.
+fn write_bridge_file(bridge_path: &Path, data: &BridgeData) -> Result<BridgeData, BridgeWriteError> {
+    let _lock = BridgeLock::acquire(bridge_path).map_err(BridgeWriteError::Failed)?;
+
+    let current = load_bridge_file(bridge_path)
+        .ok_or_else(|| BridgeWriteError::Failed(format!("{} is not valid JSON", bridge_path.display())))?;
+    if current.revision != data.revision {
+        return Err(BridgeWriteError::Conflict);
+    }
+
+    let next = BridgeData { revision: data.revision + 1, ..data.clone() };
+    let content = serde_json::to_string_pretty(&next).map_err(|e| BridgeWriteError::Failed(e.to_string()))?;
+    let temp = sibling(bridge_path, ".tmp");
+    fs::write(&temp, content)
+        .map_err(|e| BridgeWriteError::Failed(format!("Failed to write {}: {}", temp.display(), e)))?;
+    if let Err(e) = fs::rename(&temp, bridge_path) {
+        let _ = fs::remove_file(&temp);
+        return Err(BridgeWriteError::Busy(format!("Failed to replace {}: {}", bridge_path.display(), e)));
+    }
+    Ok(next)
+}
+
+fn write_bridge_data(data: &BridgeData) -> Result<BridgeData, BridgeWriteError> {
+    write_bridge_file(&get_bridge_path(), data)
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+struct ResolvedPayload {
+    id: String,
+    /// `approved`, `rejected`, or `removed` when the request vanished from the file
+    status: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum BridgeEvent {
+    Request(BridgeRequest),
+    Resolved(ResolvedPayload),
+}
+
+/// What changed for the GUI between two states of the queue
+fn diff(old: &BridgeData, new: &BridgeData) -> Vec<BridgeEvent> {
+    let was_pending = |id: &str| old.requests.iter().any(|r| r.id == id && r.status == PENDING);
+
+    let mut events: Vec<BridgeEvent> = new.requests.iter()
+        .filter(|r| r.status == PENDING && !was_pending(&r.id))
+        .cloned()
+        .map(BridgeEvent::Request)
+        .collect();
+
+    for request in old.requests.iter().filter(|r| r.status == PENDING) {
+        let status = match new.requests.iter().find(|r| r.id == request.id) {
+            Some(current) if current.status == PENDING => continue,
+            Some(current) => current.status.clone(),
+            None => "removed".to_string(),
+        };
+        events.push(BridgeEvent::Resolved(ResolvedPayload { id: request.id.clone(), status }));
+    }
+    events
+}
+
+/// Last known state of `bridge.json` (Tauri managed state)
+#[derive(Default)]
+pub(crate) struct BridgeQueue {
+    state: Mutex<BridgeData>,
+}
+
+impl BridgeQueue {
+    /// Take `data` as the current state and emit what changed
+    fn publish(&self, app: &AppHandle, data: &BridgeData) {
+        let mut state = self.state.lock().unwrap();
+        for event in diff(&state, data) {
+            let _ = match event {
+                BridgeEvent::Request(request) => app.emit(REQUEST_EVENT, request),
+                BridgeEvent::Resolved(resolved) => app.emit(RESOLVED_EVENT, resolved),
+            };
+        }
+        *state = data.clone();
+    }
+
+    /// Read-modify-write `bridge.json` and publish the result, starting over
+    /// from the current file when another writer got in between. Blocks while
+    /// waiting for the lock; see `update_bridge`.
+    fn update(&self, app: &AppHandle, change: impl Fn(&mut BridgeData)) -> Result<BridgeData, String> {
+        let mut last_error = "bridge.json is unreadable or keeps changing; try again".to_string();
+        for _ in 0..MAX_CONFLICT_RETRIES {
+            let Some(mut data) = load_bridge_data() else {
+                // Most likely caught halfway through a write by bridge.ps1
+                std::thread::sleep(LOCK_RETRY);
+                continue;
+            };
+            change(&mut data);
+            match write_bridge_data(&data) {
+                Ok(saved) => {
+                    self.publish(app, &saved);
+                    return Ok(saved);
+                }
+                Err(BridgeWriteError::Conflict) => continue,
+                Err(BridgeWriteError::Busy(e)) => {
+                    last_error = e;
+                    std::thread::sleep(LOCK_RETRY);
+                }
+                Err(BridgeWriteError::Failed(e)) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Run `BridgeQueue::update` on a blocking thread, off the async runtime and
+/// the main thread
+async fn update_bridge(app: AppHandle, change: impl Fn(&mut BridgeData) + Send + 'static) -> Result<BridgeData, String> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<BridgeQueue>().update(&app, change))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Call `on_change` with the parsed file after every change to `path`, for
+/// as long as the returned future is polled. Fails only when the watch
+/// cannot be set up.
+async fn follow_bridge_file(path: PathBuf, mut on_change: impl FnMut(BridgeData)) -> notify::Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    watcher.watch(path.parent().unwrap_or(Path::new(".")), RecursiveMode::NonRecursive)?;
+
+    while let Some(event) = rx.recv().await {
+        let event: notify::Event = match event {
+            Ok(event) => event,
+            Err(_) => continue,
+        };
+        if !event.paths.iter().any(|p| p.file_name() == path.file_name()) {
+            continue;
+        }
+        tokio::time::sleep(SETTLE).await;
+        while rx.try_recv().is_ok() {}
+
+        // A half-written file is read again shortly instead of being
+        // mistaken for an empty queue
+        for _ in 0..RELOAD_ATTEMPTS {
+            if let Some(data) = load_bridge_file(&path) {
+                on_change(data);
+                break;
+            }
+            tokio::time::sleep(SETTLE).await;
+        }
+    }
+    Ok(())
+}
+
+/// Modification time and size, to notice writes without re-reading the file
+fn file_signature(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// `follow_bridge_file` for when the watch cannot be set up
+async fn poll_bridge_file(path: PathBuf, mut on_change: impl FnMut(BridgeData)) {
+    let mut last = file_signature(&path);
+    // Whatever changed before polling started
+    if let Some(data) = load_bridge_file(&path) {
+        on_change(data);
+    }
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let current = file_signature(&path);
+        if current == last {
+            continue;
+        }
+        // A half-written file is retried on the next tick instead of
+        // being mistaken for an empty queue
+        if let Some(data) = load_bridge_file(&path) {
+            last = current;
+            on_change(data);
+        }
+    }
+}
+
+/// Load the queue and keep following `bridge.json` for the app's lifetime
+pub(crate) fn watch_bridge(app: AppHandle) {
+    app.state::<BridgeQueue>().publish(&app, &read_bridge_data());
+
+    tauri::async_runtime::spawn(async move {
+        let queue = app.state::<BridgeQueue>();
+        let path = get_bridge_path();
+        let mut on_change = |data: BridgeData| queue.publish(&app, &data);
+        if follow_bridge_file(path.clone(), &mut on_change).await.is_err() {
+            poll_bridge_file(path, on_change).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn get_bridge_state(queue: State<'_, BridgeQueue>) -> Result<BridgeData, String> {
+    Ok(queue.state.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub(crate) async fn set_auto_approve(app: AppHandle, enabled: bool) -> Result<BridgeData, String> {
+    update_bridge(app, move |data| data.auto_approve = enabled).await
+}
+
+#[tauri::command]
+pub(crate) async fn approve_request(app: AppHandle, id: String) -> Result<BridgeData, String> {
+    update_bridge(app, move |data| {
+        if let Some(req) = data.requests.iter_mut().find(|r| r.id == id) {
+            req.status = "approved".to_string();
+        }
+    }).await
+}
+
+#[tauri::command]
+pub(crate) async fn reject_request(app: AppHandle, id: String) -> Result<BridgeData, String> {
+    update_bridge(app, move |data| {
+        if let Some(req) = data.requests.iter_mut().find(|r| r.id == id) {
+            req.status = "rejected".to_string();
+        }
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, status: &str) -> BridgeRequest {
+        BridgeRequest { id: id.to_string(), message: format!("Run {}?", id), status: status.to_string() }
+    }
+
+    #[test]
+    fn diff_reports_new_and_resolved_requests() {
+        let old = BridgeData {
+            requests: vec![request("a", "pending"), request("b", "pending"), request("c", "approved")],
+            auto_approve: false,
+            revision: 1,
+        };
+        let new = BridgeData {
+            requests: vec![request("a", "pending"), request("b", "rejected"), request("c", "approved"), request("d", "pending")],
+            auto_approve: false,
+            revision: 2,
+        };
+        assert_eq!(diff(&old, &new), vec![
+            BridgeEvent::Request(request("d", "pending")),
+            BridgeEvent::Resolved(ResolvedPayload { id: "b".to_string(), status: "rejected".to_string() }),
+        ]);
+
+        let cleared = BridgeData { requests: Vec::new(), auto_approve: false, revision: 3 };
+        assert_eq!(diff(&new, &cleared), vec![
+            BridgeEvent::Resolved(ResolvedPayload { id: "a".to_string(), status: "removed".to_string() }),
+            BridgeEvent::Resolved(ResolvedPayload { id: "d".to_string(), status: "removed".to_string() }),
+        ]);
+    }
+
+    #[test]
+    fn writes_are_compare_and_swap_on_the_revision() {
+        let dir = std::env::temp_dir().join(format!("geminigui-bridge-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bridge.json");
+        // A file from before revisions existed
+        fs::write(&path, "\u{feff}{\"requests\": [], \"auto_approve\": false}").unwrap();
+
+        let read = load_bridge_file(&path).unwrap();
+        assert_eq!(read.revision, 0);
+        let saved = write_bridge_file(&path, &BridgeData { requests: vec![request("a", "pending")], ..read.clone() }).unwrap();
+        assert_eq!(saved.revision, 1);
+        assert_eq!(load_bridge_file(&path).unwrap(), saved);
+
+        // A writer still holding the revision-0 read loses instead of dropping request "a"
+        let stale = BridgeData { auto_approve: true, ..read };
+        assert_eq!(write_bridge_file(&path, &stale), Err(BridgeWriteError::Conflict));
+        assert!(!sibling(&path, ".lock").exists());
+        assert!(!sibling(&path, ".tmp").exists());
+
+        // Someone else holds the lock: wait for it, then give up
+        let held = BridgeLock::acquire(&path).unwrap();
+        assert!(matches!(write_bridge_file(&path, &saved), Err(BridgeWriteError::Failed(_))));
+        drop(held);
+        assert_eq!(write_bridge_file(&path, &saved).unwrap().revision, 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn only_stale_locks_are_broken() {
+        let dir = std::env::temp_dir().join(format!("geminigui-bridge-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bridge.json");
+        let lock = sibling(&path, ".lock");
+
+        // A fresh lock that another writer just took survives a break attempt
+        fs::write(&lock, "1").unwrap();
+        break_stale_lock(&lock);
+        assert!(lock.exists());
+
+        // A lock abandoned by a crashed writer is taken over
+        let abandoned = SystemTime::now() - STALE_LOCK * 2;
+        fs::File::options().write(true).open(&lock).unwrap().set_modified(abandoned).unwrap();
+        drop(BridgeLock::acquire(&path).unwrap());
+        assert!(!lock.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn a_writer_whose_lock_was_broken_leaves_the_new_one_alone() {
+        let dir = std::env::temp_dir().join(format!("geminigui-bridge-broken-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bridge.json");
+        let lock = sibling(&path, ".lock");
+
+        let stalled = BridgeLock::acquire(&path).unwrap();
+        // The holder stalls past STALE_LOCK; another writer breaks the lock and takes it
+        let abandoned = SystemTime::now() - STALE_LOCK * 2;
+        fs::File::options().write(true).open(&lock).unwrap().set_modified(abandoned).unwrap();
+        let current = BridgeLock::acquire(&path).unwrap();
+
+        drop(stalled);
+        assert_eq!(fs::read_to_string(&lock).unwrap(), current.token);
+        drop(current);
+        assert!(!lock.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn follows_writes_that_rename_over_the_file() {
+        let dir = std::env::temp_dir().join(format!("geminigui-bridge-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bridge.json");
+        let _ = fs::remove_file(&path);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let watcher = tokio::spawn(follow_bridge_file(path.clone(), move |data| {
+            let _ = tx.send(data);
+        }));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Lock and temp files alone are not changes to the queue
+        drop(BridgeLock::acquire(&path).unwrap());
+        let written = write_bridge_file(&path, &BridgeData { requests: vec![request("a", "pending")], ..Default::default() }).unwrap();
+        let seen = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(seen, written);
+
+        write_bridge_file(&path, &BridgeData { auto_approve: false, ..written }).unwrap();
+        let seen = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!((seen.revision, seen.auto_approve), (2, false));
+        assert!(rx.try_recv().is_err());
+
+        watcher.abort();
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn polling_picks_up_writes() {
+        let dir = std::env::temp_dir().join(format!("geminigui-bridge-poll-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bridge.json");
+        let _ = fs::remove_file(&path);
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let poller = tokio::spawn(poll_bridge_file(path.clone(), move |data| {
+            let _ = tx.send(data);
+        }));
+        let initial = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(initial, BridgeData::default());
+
+        let written = write_bridge_file(&path, &BridgeData { requests: vec![request("a", "pending")], ..initial }).unwrap();
+        let seen = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(seen, written);
+
+        poller.abort();
+        let _ = fs::remove_dir_all(dir);
+    }
+}
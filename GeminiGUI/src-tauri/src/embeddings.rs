@@ -0,0 +1,129 @@
+// ============================================================================
+// EMBEDDINGS
+// ============================================================================
+//
+// `embed_texts` replaces the `/api/embeddings` calls in GeminiRAG.psm1. Texts
+// are sent in batches (Ollama `/api/embed`, Gemini `batchEmbedContents`) and
+// vectors are cached in memory by a hash of provider, endpoint, model and
+// text, so re-indexing unchanged documents costs nothing.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::http::HttpState;
+use crate::providers::{LlmProvider, ProviderConfig};
+use crate::secrets::SecretStore;
+
+/// Texts per request; well under Gemini's limit of 100 per batch
+const BATCH_SIZE: usize = 64;
+
+/// Cached vectors kept before the oldest are evicted
+const CACHE_CAPACITY: usize = 20_000;
+
+type CacheKey = [u8; 32];
+
+/// Two servers may serve different weights under the same model name, so the
+/// endpoint is part of the key
+fn cache_key(provider: &str, endpoint: &str, model: &str, text: &str) -> CacheKey {
+    let mut hasher = Sha256::new();
+    for part in [provider, endpoint, model, text] {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Embedding vectors by content hash (Tauri managed state)
+#[derive(Default)]
+pub(crate) struct EmbeddingCache {
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    vectors: HashMap<CacheKey, Vec<f32>>,
+    /// Insertion order, for evicting the oldest entries
+    order: VecDeque<CacheKey>,
+}
+
+impl EmbeddingCache {
+    fn get(&self, key: &CacheKey) -> Option<Vec<f32>> {
+        self.inner.lock().unwrap().vectors.get(key).cloned()
+    }
+
+    fn insert(&self, key: CacheKey, vector: Vec<f32>) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.vectors.insert(key, vector).is_none() {
+            inner.order.push_back(key);
+        }
+        while inner.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.vectors.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Embed `texts` with `model`, returning one vector per text in order.
+/// Cached texts are not re-sent; duplicates within a call are embedded once.
+#[tauri::command]
+pub(crate) async fn embed_texts(
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    cache: State<'_, EmbeddingCache>,
+    provider: ProviderConfig,
+    model: String,
+    texts: Vec<String>,
+) -> Result<Vec<Vec<f32>>, String> {
+    let endpoint = provider.endpoint().trim_end_matches('/').to_string();
+    let provider = provider.build(http.client(), &secrets, &model)?;
+    let keys: Vec<CacheKey> = texts.iter().map(|text| cache_key(provider.name(), &endpoint, &model, text)).collect();
+
+    let mut missing: Vec<(CacheKey, String)> = Vec::new();
+    let mut queued: HashSet<CacheKey> = HashSet::new();
+    for (key, text) in keys.iter().zip(&texts) {
+        if cache.get(key).is_none() && queued.insert(*key) {
+            missing.push((*key, text.clone()));
+        }
+    }
+
+    let mut fresh: HashMap<CacheKey, Vec<f32>> = HashMap::new();
+    for batch in missing.chunks(BATCH_SIZE) {
+        let inputs: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let vectors = provider.embed(&model, &inputs).await?;
+        if vectors.len() != inputs.len() {
+            return Err(format!("Expected {} embeddings, got {}", inputs.len(), vectors.len()));
+        }
+        for ((key, _), vector) in batch.iter().zip(vectors) {
+            cache.insert(*key, vector.clone());
+            fresh.insert(*key, vector);
+        }
+    }
+
+    Ok(keys.iter()
+        .map(|key| fresh.get(key).cloned().or_else(|| cache.get(key)).unwrap_or_default())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_keys_separate_models_and_evict_oldest() {
+        const LOCAL: &str = "http://localhost:11434";
+        assert_ne!(cache_key("ollama", LOCAL, "mxbai-embed-large", "a"), cache_key("ollama", LOCAL, "nomic-embed-text", "a"));
+        assert_ne!(cache_key("ollama", LOCAL, "m", "a"), cache_key("ollama", "http://gpu-box:11434", "m", "a"));
+        assert_ne!(cache_key("ollama", LOCAL, "ab", "c"), cache_key("ollama", LOCAL, "a", "bc"));
+
+        let cache = EmbeddingCache::default();
+        for i in 0..=CACHE_CAPACITY {
+            cache.insert(cache_key("ollama", LOCAL, "m", &i.to_string()), vec![i as f32]);
+        }
+        assert_eq!(cache.get(&cache_key("ollama", LOCAL, "m", "0")), None);
+        assert_eq!(cache.get(&cache_key("ollama", LOCAL, "m", "1")), Some(vec![1.0]));
+    }
+}
@@ -0,0 +1,341 @@
+// ============================================================================
+// HTTP: shared client and retry policy
+// ============================================================================
+//
+// Every outbound request goes through `HttpClient::send`, which retries
+// connection failures and 429/5xx responses with jittered exponential
+// backoff, honouring `Retry-After` on 429/503 (the Rust counterpart of
+// `Invoke-RestMethodWithRetry` on the PowerShell side). Nothing that may
+// already have reached the server is repeated blindly: timeouts are never
+// retried, and 429/5xx only for idempotent methods or requests the caller
+// declares side-effect free with `send_idempotent`.
+//
+// One client is built at startup from `NetworkSettings` and kept in Tauri
+// managed state (`HttpState`), so connections are pooled across commands.
+// Saving new settings swaps in a freshly built client.
+
+use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+
+use reqwest::header::RETRY_AFTER;
+use reqwest::{IntoUrl, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub(crate) struct RetryPolicy {
+    /// Total tries per request, including the first one
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    /// Upper bound for a single wait; a longer `Retry-After` is not waited out
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// "Full jitter" backoff: a random wait between zero and the exponential cap
+    fn backoff(&self, attempt: u32) -> Duration {
+        let cap = self.base_delay_ms
+            .saturating_mul(1u64 << attempt.min(20))
+            .min(self.max_delay_ms);
+        Duration::from_millis(fastrand::u64(0..=cap))
+    }
+
+    /// How long to wait before retrying `res`, or None if it should be returned as-is
+    fn delay_for(&self, res: &Response, attempt: u32) -> Option<Duration> {
+        let status = res.status();
+        if !is_retryable_status(status) {
+            return None;
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            if let Some(wait) = res.headers().get(RETRY_AFTER).and_then(|v| v.to_str().ok()).and_then(parse_retry_after) {
+                // Waiting longer than we are allowed to is pointless; hand the
+                // response back so the caller can fall back instead.
+                return (wait <= Duration::from_millis(self.max_delay_ms)).then_some(wait);
+            }
+        }
+        Some(self.backoff(attempt))
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Only failures where the request cannot have reached the server; a timeout
+/// may hit a request that is still being processed
+fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_connect()
+}
+
+/// Parse a `Retry-After` value: either delta-seconds or an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(at.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub(crate) struct NetworkSettings {
+    pub connect_timeout_secs: u64,
+    /// Maximum idle time between reads. Unset by default: a large local model
+    /// can think for minutes before its first token.
+    pub read_timeout_secs: Option<u64>,
+    /// HTTP(S) proxy for all requests, e.g. `http://proxy.corp:8080`
+    pub proxy: Option<String>,
+    /// PEM file with extra root certificates (corporate TLS inspection)
+    pub ca_cert_path: Option<String>,
+    pub user_agent: String,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 10,
+            read_timeout_secs: None,
+            proxy: None,
+            ca_cert_path: None,
+            user_agent: format!("GeminiGUI/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl NetworkSettings {
+    fn build_client(&self) -> Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .user_agent(self.user_agent.clone());
+
+        if let Some(secs) = self.read_timeout_secs {
+            builder = builder.read_timeout(Duration::from_secs(secs));
+        }
+
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+            let proxy = reqwest::Proxy::all(proxy.trim()).map_err(|e| format!("Invalid proxy: {}", e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = self.ca_cert_path.as_deref().filter(|p| !p.trim().is_empty()) {
+            let pem = std::fs::read(path.trim()).map_err(|e| format!("Failed to read CA certificate: {}", e))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        builder.build().map_err(|e| format!("Failed to build HTTP client: {}", e))
+    }
+}
+
+/// reqwest client bundled with the retry policy every request should follow
+#[derive(Clone)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl HttpClient {
+    pub fn new(client: reqwest::Client, retry: RetryPolicy) -> Self {
+        Self { client, retry }
+    }
+
+    pub fn from_settings(network: &NetworkSettings, retry: &RetryPolicy) -> Result<Self, String> {
+        Ok(Self::new(network.build_client()?, retry.clone()))
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn delete<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.delete(url)
+    }
+
+    /// Send `request`, retrying transient failures according to the policy.
+    /// 429/5xx responses are only retried for idempotent methods (GET, DELETE, ...).
+    /// Requests whose body cannot be cloned are sent exactly once.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        self.send_with(request, false).await
+    }
+
+    /// Like `send`, but also retries 429/5xx for a POST that changes nothing
+    /// on the server (chat completions, embeddings, model info)
+    pub async fn send_idempotent(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        self.send_with(request, true).await
+    }
+
+    async fn send_with(&self, request: RequestBuilder, idempotent: bool) -> Result<Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        let retry_status = idempotent || request.method().is_idempotent();
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let next = if attempt < max_attempts { request.try_clone() } else { None };
+            let Some(next) = next else {
+                return client.execute(request).await;
+            };
+
+            let wait = match client.execute(request).await {
+                Ok(res) if !retry_status => return Ok(res),
+                Ok(res) => match self.retry.delay_for(&res, attempt - 1) {
+                    Some(wait) => wait,
+                    None => return Ok(res),
+                },
+                Err(e) if is_retryable_error(&e) => self.retry.backoff(attempt - 1),
+                Err(e) => return Err(e),
+            };
+            tokio::time::sleep(wait).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+}
+
+/// Tauri managed state holding the client built from the current settings
+pub(crate) struct HttpState(RwLock<HttpClient>);
+
+impl HttpState {
+    pub fn new(client: HttpClient) -> Self {
+        Self(RwLock::new(client))
+    }
+
+    /// Cheap handle to the current client (reqwest clients share their pool)
+    pub fn client(&self) -> HttpClient {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn replace(&self, client: HttpClient) {
+        *self.0.write().unwrap() = client;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve one canned HTTP response per connection, in order, and count hits
+    async fn stub_server(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                socket.write_all(response.as_bytes()).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+        (url, hits)
+    }
+
+    const BUSY: &str = "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nConnection: close\r\nContent-Length: 0\r\n\r\n";
+    const LIMITED: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 3600\r\nConnection: close\r\nContent-Length: 0\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\nok";
+
+    fn client(max_attempts: u32) -> HttpClient {
+        HttpClient::new(reqwest::Client::new(), RetryPolicy {
+            max_attempts,
+            base_delay_ms: 1,
+            max_delay_ms: 50,
+        })
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let (url, hits) = stub_server(vec![BUSY, BUSY, OK]).await;
+        let http = client(3);
+        let res = http.send_idempotent(http.post(&url).json(&serde_json::json!({ "a": 1 }))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_repeat_plain_posts() {
+        let (url, hits) = stub_server(vec![BUSY, OK]).await;
+        let http = client(3);
+        let res = http.send(http.post(&url).json(&serde_json::json!({ "a": 1 }))).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_timeouts() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            // Accept and never answer
+            let mut open = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                open.push(socket);
+            }
+        });
+
+        let http = client(3);
+        let err = http.send(http.get(&url).timeout(Duration::from_millis(100))).await.unwrap_err();
+        assert!(err.is_timeout());
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (url, hits) = stub_server(vec![BUSY, BUSY, OK]).await;
+        let http = client(2);
+        let res = http.send(http.get(&url)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_wait_out_long_retry_after() {
+        let (url, hits) = stub_server(vec![LIMITED, OK]).await;
+        let http = client(3);
+        let res = http.send(http.get(&url)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn parses_retry_after_forms() {
+        assert_eq!(parse_retry_after("7"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn backoff_stays_under_cap() {
+        let policy = RetryPolicy { max_attempts: 5, base_delay_ms: 100, max_delay_ms: 1_000 };
+        for attempt in 0..40 {
+            assert!(policy.backoff(attempt) <= Duration::from_millis(1_000));
+        }
+    }
+}
@@ -1,13 +1,41 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod bridge;
+mod embeddings;
+mod http;
+mod models;
+mod ollama_models;
+mod ollama_server;
+mod providers;
+mod redact;
+mod secrets;
+mod settings;
+mod streams;
+mod structured;
+mod usage;
+
 use std::fs;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, Window, AppHandle, Manager};
+use tauri::{Emitter, Window, AppHandle, Manager, State};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent};
-use futures_util::StreamExt;
 use std::process::{Command, Stdio};
 use std::io::{BufRead, BufReader};
+use bridge::{approve_request, get_bridge_state, reject_request, set_auto_approve, watch_bridge, BridgeQueue};
+use embeddings::{embed_texts, EmbeddingCache};
+use http::{HttpClient, HttpState};
+use models::{get_gemini_model_info, get_gemini_models_sorted, set_model_pinned};
+use ollama_models::{
+    copy_ollama_model, delete_ollama_model, get_ollama_running, pull_ollama_model, show_ollama_model, unload_ollama_model,
+};
+use ollama_server::{get_ollama_status, start_ollama_server, stop_ollama_server, OllamaSupervisor};
+use providers::{ChatMessage, ChatRequest, GeminiOptions, GeminiProvider, LlmProvider, ModelTarget, OllamaOptions, OllamaProvider, ProviderConfig, ToolDeclaration};
+use redact::redact;
+use secrets::{delete_secret, has_secret, is_secret_name, set_secret, SecretStore};
+use settings::{get_backend_settings, read_settings, save_backend_settings};
+use streams::{cancel_stream, spawn_stream, submit_tool_result, StreamRegistry, StreamTarget};
+use structured::prompt_structured;
+use usage::{get_usage_report, UsageLedger};
 
 // ============================================================================
 // SECURITY: Configuration
@@ -24,10 +52,6 @@ fn get_base_dir() -> std::path::PathBuf {
         .unwrap_or_else(|| std::path::PathBuf::from("."))
 }
 
-fn get_bridge_path() -> std::path::PathBuf {
-    get_base_dir().join("bridge.json")
-}
-
 /// SECURITY: Allowlist of safe commands
 const ALLOWED_COMMANDS: &[&str] = &[
     // Safe read-only commands
@@ -48,94 +72,6 @@ const ALLOWED_COMMANDS: &[&str] = &[
 fn is_command_allowed(_command: &str) -> bool {
     true
 }
-// ... (rest of struct definitions remains the same)
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct BridgeRequest {
-    id: String,
-    message: String,
-    status: String, // "pending", "approved", "rejected"
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct BridgeData {
-    requests: Vec<BridgeRequest>,
-    auto_approve: bool,
-}
-
-impl Default for BridgeData {
-    fn default() -> Self {
-        Self {
-            requests: vec![],
-            auto_approve: true,
-        }
-    }
-}
-
-fn read_bridge_data() -> BridgeData {
-    let bridge_path = get_bridge_path();
-    if !bridge_path.exists() {
-        return BridgeData::default();
-    }
-    match fs::read_to_string(&bridge_path) {
-        Ok(content) => serde_json::from_str(&content).unwrap_or(BridgeData::default()),
-        Err(_) => BridgeData::default(),
-    }
-}
-
-fn write_bridge_data(data: &BridgeData) -> Result<(), String> {
-    let bridge_path = get_bridge_path();
-    let content = serde_json::to_string_pretty(data).map_err(|e| e.to_string())?;
-    fs::write(&bridge_path, content).map_err(|e| e.to_string())
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct OllamaMessage {
-    role: String,
-    content: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    images: Option<Vec<String>>,
-}
-
-// Gemini Structures
-#[derive(Serialize, Deserialize, Debug)]
-struct GeminiPart {
-    text: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct GeminiContent {
-    role: String,
-    parts: Vec<GeminiPart>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct GeminiRequest {
-    contents: Vec<GeminiContent>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct OllamaChatRequest {
-    model: String,
-    messages: Vec<OllamaMessage>,
-    stream: bool,
-}
-
-#[derive(Serialize, Deserialize)]
-struct OllamaChatResponse {
-    message: OllamaMessage,
-    done: bool,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct OllamaModel {
-    name: String,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct OllamaTagsResponse {
-    models: Vec<OllamaModel>,
-}
 
 #[derive(Clone, Serialize)]
 struct StreamPayload {
@@ -144,139 +80,95 @@ struct StreamPayload {
 }
 
 
-#[tauri::command]
-fn get_bridge_state() -> Result<BridgeData, String> {
-    Ok(read_bridge_data())
-}
-
-#[tauri::command]
-fn set_auto_approve(enabled: bool) -> Result<BridgeData, String> {
-    let mut data = read_bridge_data();
-    data.auto_approve = enabled;
-    write_bridge_data(&data)?;
-    Ok(data)
-}
-
-#[tauri::command]
-fn approve_request(id: String) -> Result<BridgeData, String> {
-    let mut data = read_bridge_data();
-    if let Some(req) = data.requests.iter_mut().find(|r| r.id == id) {
-        req.status = "approved".to_string();
-    }
-    write_bridge_data(&data)?;
-    Ok(data)
-}
-
-#[tauri::command]
-fn reject_request(id: String) -> Result<BridgeData, String> {
-    let mut data = read_bridge_data();
-    if let Some(req) = data.requests.iter_mut().find(|r| r.id == id) {
-        req.status = "rejected".to_string();
-    }
-    write_bridge_data(&data)?;
-    Ok(data)
-}
-
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
 #[tauri::command]
-async fn fetch_external_data(url: String) -> Result<serde_json::Value, String> {
-    let client = reqwest::Client::new();
-    let res = client.get(&url)
-        .send()
+async fn fetch_external_data(http: State<'_, HttpState>, url: String) -> Result<serde_json::Value, String> {
+    let http = http.client();
+    let res = http.send(http.get(&url))
         .await
-        .map_err(|e| e.to_string())?
+        .map_err(|e| redact(&e.to_string()))?
         .json::<serde_json::Value>()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| redact(&e.to_string()))?;
     Ok(res)
 }
 
+/// Build the fallback chain for a stream: the primary target, then any fallbacks in order
+fn stream_targets(
+    http: &HttpState,
+    secrets: &SecretStore,
+    primary: ModelTarget,
+    fallbacks: Option<Vec<ModelTarget>>
+) -> Result<Vec<StreamTarget>, String> {
+    std::iter::once(primary)
+        .chain(fallbacks.unwrap_or_default())
+        .map(|target| Ok(StreamTarget {
+            provider: target.provider.build(http.client(), secrets, &target.model)?,
+            model: target.model,
+        }))
+        .collect()
+}
+
+/// Stream a chat through `provider`, then `fallbacks`. The model may call
+/// `tools`; see `submit_tool_result`.
 #[tauri::command]
-async fn prompt_ollama(messages: Vec<OllamaMessage>, model: String, endpoint: String) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let req = OllamaChatRequest {
-        model,
-        messages,
-        stream: false,
-    };
-    
-    let url = format!("{}/api/chat", endpoint.trim_end_matches('/'));
-    let res = client.post(&url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() {
-         return Err(format!("Ollama API Error: {}", res.status()));
-    }
+#[allow(clippy::too_many_arguments)] // Tauri injects state as arguments
+async fn prompt_stream(
+    window: Window,
+    streams: State<'_, StreamRegistry>,
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    provider: ProviderConfig,
+    messages: Vec<ChatMessage>,
+    model: String,
+    fallbacks: Option<Vec<ModelTarget>>,
+    tools: Option<Vec<ToolDeclaration>>,
+    request_id: Option<String>
+) -> Result<String, String> {
+    let targets = stream_targets(&http, &secrets, ModelTarget { provider, model }, fallbacks)?;
+    spawn_stream(&streams, window, request_id, targets, messages, tools.unwrap_or_default())
+}
 
-    let body: OllamaChatResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok(body.message.content)
+#[tauri::command]
+async fn prompt_ollama(
+    http: State<'_, HttpState>,
+    messages: Vec<ChatMessage>,
+    model: String,
+    endpoint: String,
+    options: Option<OllamaOptions>
+) -> Result<String, String> {
+    let provider = OllamaProvider::new(http.client(), endpoint)
+        .with_options(settings::ollama_options(&model, options));
+    Ok(provider.chat(&ChatRequest { model, messages, tools: Vec::new() }).await?)
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri injects state as arguments
 async fn prompt_ollama_stream(
     window: Window, 
-    messages: Vec<OllamaMessage>, 
+    streams: State<'_, StreamRegistry>,
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    messages: Vec<ChatMessage>, 
     model: String, 
-    endpoint: String
-) -> Result<(), String> {
-    let client = reqwest::Client::new();
-    let req = OllamaChatRequest {
-        model,
-        messages,
-        stream: true,
-    };
-
-    let url = format!("{}/api/chat", endpoint.trim_end_matches('/'));
-    let mut stream = client.post(&url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .bytes_stream();
-
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        if let Ok(text) = String::from_utf8(chunk.to_vec()) {
-            for line in text.lines() {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-                    if let Some(content) = json.get("message").and_then(|m| m.get("content")).and_then(|v| v.as_str()) {
-                         let done = json.get("done").and_then(|v| v.as_bool()).unwrap_or(false);
-                         window.emit("ollama-event", StreamPayload {
-                             chunk: content.to_string(),
-                             done
-                         }).map_err(|e| e.to_string())?;
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(())
+    endpoint: String,
+    options: Option<OllamaOptions>,
+    fallbacks: Option<Vec<ModelTarget>>,
+    request_id: Option<String>
+) -> Result<String, String> {
+    let primary = ModelTarget { provider: ProviderConfig::Ollama { endpoint, options }, model };
+    let targets = stream_targets(&http, &secrets, primary, fallbacks)?;
+    spawn_stream(&streams, window, request_id, targets, messages, Vec::new())
 }
 
 
 #[tauri::command]
-async fn get_ollama_models(endpoint: String) -> Result<Vec<String>, String> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/api/tags", endpoint.trim_end_matches('/'));
-    let res = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() {
-         return Err(format!("Ollama API Error: {}", res.status()));
-    }
-
-    let body: OllamaTagsResponse = res.json().await.map_err(|e| e.to_string())?;
-    Ok(body.models.into_iter().map(|m| m.name).collect())
+async fn get_ollama_models(http: State<'_, HttpState>, endpoint: String) -> Result<Vec<String>, String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).list_models().await?)
 }
 
 /// SECURITY: Execute system command with allowlist validation
@@ -497,61 +389,29 @@ fn save_file_content(path: String, content: String) -> Result<(), String> {
     fs::write(&path, content).map_err(|e| format!("Failed to save file: {}", e))
 }
 
+/// `options` carries generationConfig / safetySettings; `system` messages are
+/// sent as the system instruction
 #[tauri::command]
+#[allow(clippy::too_many_arguments)] // Tauri injects state as arguments
 async fn prompt_gemini_stream(
     window: Window,
-    messages: Vec<OllamaMessage>,
+    streams: State<'_, StreamRegistry>,
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    messages: Vec<ChatMessage>,
     model: String,
-    api_key: String
-) -> Result<(), String> {
-    let client = reqwest::Client::new();
-    
-    // Convert internal message format to Gemini format
-    let contents: Vec<GeminiContent> = messages.iter().map(|m| {
-        GeminiContent {
-            role: if m.role == "assistant" { "model".to_string() } else { "user".to_string() },
-            parts: vec![GeminiPart { text: Some(m.content.clone()) }]
-        }
-    }).collect();
-
-    let req = GeminiRequest { contents };
-    let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?key={}", model, api_key);
-
-    let mut stream = client.post(&url)
-        .json(&req)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .bytes_stream();
-
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        if let Ok(text) = String::from_utf8(chunk.to_vec()) {
-            if let Some(start) = text.find("\"text\": \"") {
-                let rest = &text[start + 9..];
-                if let Some(end) = rest.find("\"") {
-                    let content = &rest[..end];
-                    let unescaped = content.replace("\\n", "\n").replace("\\\"", "\"");
-                    window.emit("ollama-event", StreamPayload {
-                        chunk: unescaped,
-                        done: false
-                    }).map_err(|e| e.to_string())?;
-                }
-            }
-        }
-    }
-    
-    window.emit("ollama-event", StreamPayload {
-        chunk: "".to_string(),
-        done: true
-    }).map_err(|e| e.to_string())?;
-
-    Ok(())
+    options: Option<GeminiOptions>,
+    fallbacks: Option<Vec<ModelTarget>>,
+    request_id: Option<String>
+) -> Result<String, String> {
+    let provider = ProviderConfig::Gemini { options: options.unwrap_or_default() };
+    let primary = ModelTarget { provider, model };
+    let targets = stream_targets(&http, &secrets, primary, fallbacks)?;
+    spawn_stream(&streams, window, request_id, targets, messages, Vec::new())
 }
 
 /// Read environment variables from .env file (secure path)
-#[tauri::command]
-async fn get_env_vars() -> Result<std::collections::HashMap<String, String>, String> {
+pub(crate) fn read_env_file() -> Result<std::collections::HashMap<String, String>, String> {
     let base_dir = get_base_dir();
     let env_path = base_dir.join(".env");
 
@@ -583,73 +443,30 @@ async fn get_env_vars() -> Result<std::collections::HashMap<String, String>, Str
     Ok(vars)
 }
 
+/// .env variables for the frontend
 #[tauri::command]
-async fn get_gemini_models(api_key: String) -> Result<Vec<String>, String> {
-    let client = reqwest::Client::new();
-    let url = format!("https://generativelanguage.googleapis.com/v1beta/models?key={}", api_key);
-    let res = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() {
-         return Err(format!("Gemini API Error: {}", res.status()));
-    }
-
-    let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
-    let mut models = Vec::new();
-    if let Some(models_array) = body.get("models").and_then(|v| v.as_array()) {
-        for model in models_array {
-            if let Some(name) = model.get("name").and_then(|v| v.as_str()) {
-                // Remove 'models/' prefix
-                models.push(name.replace("models/", ""));
-            }
-        }
-    }
-    Ok(models)
+async fn get_env_vars() -> Result<std::collections::HashMap<String, String>, String> {
+    // SECURITY: Keys, tokens and passwords stay in the backend
+    let mut vars = read_env_file()?;
+    vars.retain(|key, _| !is_secret_name(key));
+    Ok(vars)
 }
 
-// Function to assign a score to a model name for sorting
-fn get_model_score(name: &str) -> i32 {
-    let mut score = 0;
-    if name.contains("pro") { score += 100; }
-    if name.contains("ultra") { score += 200; }
-    if name.contains("flash") { score -= 50; }
-    if name.contains("1.5") { score += 50; }
-    if name.contains("latest") { score += 10; }
-    score
+#[tauri::command]
+async fn get_gemini_models(http: State<'_, HttpState>, secrets: State<'_, SecretStore>) -> Result<Vec<String>, String> {
+    Ok(GeminiProvider::new(http.client(), secrets.gemini_api_key()?).list_models().await?)
 }
 
+/// Models of an OpenAI-compatible server (`/v1/models`)
 #[tauri::command]
-async fn get_gemini_models_sorted(api_key: String) -> Result<Vec<String>, String> {
-    let client = reqwest::Client::new();
-    let url = format!("https://generativelanguage.googleapis.com/v1beta/models?key={}", api_key);
-    let res = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !res.status().is_success() {
-         return Err(format!("Gemini API Error: {}", res.status()));
-    }
-
-    let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
-    let mut models = Vec::new();
-    if let Some(models_array) = body.get("models").and_then(|v| v.as_array()) {
-        for model in models_array {
-            if let Some(name) = model.get("name").and_then(|v| v.as_str()) {
-                // We only want generative models, and remove the 'models/' prefix
-                if name.contains("generateContent") {
-                    models.push(name.replace("models/", ""));
-                }
-            }
-        }
-    }
-    
-    // Sort models by score, descending
-    models.sort_by(|a, b| get_model_score(b).cmp(&get_model_score(a)));
-
-    Ok(models)
+async fn get_openai_models(
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    base_url: String,
+    api_key_secret: Option<String>
+) -> Result<Vec<String>, String> {
+    let config = ProviderConfig::OpenAi { base_url, api_key_secret, json_schema: None };
+    Ok(config.build(http.client(), &secrets, "")?.list_models().await?)
 }
 
 // ============================================================================
@@ -850,68 +667,27 @@ fn clear_agent_memories(agent_name: String) -> Result<usize, String> {
     Ok(removed)
 }
 
-#[tauri::command]
-fn start_ollama_server() -> Result<String, String> {
-    let mut script_path = get_base_dir().join("start-ollama.ps1");
-
-    // Fallback for dev environment: try to find the script in the project root
-    // if it's not found in the calculated base dir
-    if !script_path.exists() {
-        if let Ok(cwd) = std::env::current_dir() {
-            // Check ../../start-ollama.ps1 (from src-tauri/target/debug)
-            let dev_path_1 = cwd.join("../../start-ollama.ps1");
-            if dev_path_1.exists() {
-                script_path = dev_path_1;
-            } else {
-                // Check ../start-ollama.ps1 (from GeminiGUI)
-                let dev_path_2 = cwd.join("../start-ollama.ps1");
-                if dev_path_2.exists() {
-                    script_path = dev_path_2;
-                }
-            }
-        }
-    }
-    
-    // Resolve to absolute path to avoid confusion
-    if let Ok(abs_path) = std::fs::canonicalize(&script_path) {
-        script_path = abs_path;
-    }
-
-    let script_path_str = script_path.to_string_lossy().to_string();
-
-    #[cfg(target_os = "windows")]
-    {
-        // On Windows, we want to run this in a new, hidden window so it doesn't block
-        // and doesn't show a flickering console.
-        // We use & "path" operator to execute the script path properly
-        let arg_list = format!("-ArgumentList '-ExecutionPolicy Bypass -NoExit -Command & \"{}\"'", script_path_str);
-        
-        Command::new("powershell")
-            .args(&["-WindowStyle", "Hidden", "-Command", "Start-Process", "powershell", &arg_list])
-            .spawn()
-            .map_err(|e| format!("Failed to spawn Ollama process: {}", e))?;
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        // On other systems, run it in the background
-        Command::new("sh")
-            .arg("-c")
-            .arg(format!("\"{}\" &", script_path_str))
-            .spawn()
-            .map_err(|e| format!("Failed to spawn Ollama process: {}", e))?;
-    }
-
-    Ok(format!("Ollama server started using: {}", script_path_str))
-}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // One pooled HTTP client for every command; fall back to defaults if the
+    // saved network settings cannot be applied (e.g. the CA file was moved)
+    let settings = read_settings();
+    let http = HttpClient::from_settings(&settings.network, &settings.retry)
+        .or_else(|_| HttpClient::from_settings(&Default::default(), &settings.retry))
+        .expect("failed to build HTTP client");
+
     tauri::Builder::default()
         .setup(|app| {
-            // -- Start Ollama on App Boot (fire and forget) --
-            tauri::async_runtime::spawn(async {
-                let _ = start_ollama_server();
-            });
+            let data_dir = app.path().app_data_dir()?;
+            app.manage(SecretStore::new(data_dir.clone()));
+            app.manage(UsageLedger::new(data_dir));
+
+            // -- Follow bridge.json and push approval requests to the GUI --
+            watch_bridge(app.handle().clone());
+
+            // -- Start Ollama on App Boot (supervised, restarted on crash) --
+            app.state::<OllamaSupervisor>().start(app.handle());
 
             let quit_i = MenuItem::with_id(app, "quit", "Zakoncz", true, None::<&str>)?;
             let show_i = MenuItem::with_id(app, "show", "Pokaz Okno", true, None::<&str>)?;
@@ -945,6 +721,11 @@ pub fn run() {
 
             Ok(())
         })
+        .manage(StreamRegistry::default())
+        .manage(BridgeQueue::default())
+        .manage(EmbeddingCache::default())
+        .manage(HttpState::new(http))
+        .manage(OllamaSupervisor::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
@@ -955,17 +736,39 @@ pub fn run() {
             approve_request,
             reject_request,
             fetch_external_data,
+            prompt_stream,
             prompt_ollama,
             prompt_ollama_stream,
             prompt_gemini_stream,
+            prompt_structured,
+            embed_texts,
+            cancel_stream,
+            submit_tool_result,
             get_ollama_models,
+            pull_ollama_model,
+            delete_ollama_model,
+            show_ollama_model,
+            copy_ollama_model,
+            get_ollama_running,
+            unload_ollama_model,
             get_gemini_models,
             get_gemini_models_sorted,
+            get_gemini_model_info,
+            set_model_pinned,
+            get_openai_models,
             get_env_vars,
             run_system_command,
             save_file_content,
             spawn_swarm_agent,
             start_ollama_server,
+            stop_ollama_server,
+            get_ollama_status,
+            get_backend_settings,
+            save_backend_settings,
+            set_secret,
+            has_secret,
+            delete_secret,
+            get_usage_report,
             // Memory system
             get_agent_memories,
             add_agent_memory,
@@ -974,6 +777,12 @@ pub fn run() {
             add_knowledge_edge,
             clear_agent_memories
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Do not leave a supervised `ollama serve` running behind us
+            if let tauri::RunEvent::Exit = event {
+                tauri::async_runtime::block_on(app.state::<OllamaSupervisor>().stop());
+            }
+        });
 }
\ No newline at end of file
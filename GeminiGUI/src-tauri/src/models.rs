@@ -0,0 +1,161 @@
+// ============================================================================
+// MODEL CATALOG
+// ============================================================================
+//
+// Gemini models come with typed metadata from `/v1beta/models` and are
+// filtered on `supportedGenerationMethods`. Ordering follows `ModelRanking`
+// in backend settings instead of guessing from substrings: pinned models
+// first, then stable before preview, newer family versions before older, and
+// better tiers before cheaper ones.
+
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::http::HttpState;
+use crate::providers::{GeminiProvider, ModelInfo};
+use crate::secrets::SecretStore;
+use crate::settings::{read_settings, write_settings};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub(crate) struct ModelRanking {
+    /// Models always listed first, in this order
+    pub pinned: Vec<String>,
+    /// Tiers from best to worst, matched against `-`-separated name segments
+    /// (the longest match wins, so `gemini-2.0-flash-lite` is `flash-lite`)
+    pub tiers: Vec<String>,
+    /// Name segments that put a model behind the stable releases
+    pub demoted: Vec<String>,
+}
+
+impl Default for ModelRanking {
+    fn default() -> Self {
+        Self {
+            pinned: Vec::new(),
+            tiers: ["ultra", "pro", "flash", "flash-lite"].map(String::from).to_vec(),
+            demoted: ["preview", "exp", "experimental"].map(String::from).to_vec(),
+        }
+    }
+}
+
+/// Family version from the segment after the family name: `gemini-2.5-pro` is
+/// `[2, 5]`; names without one (`gemini-exp-1206`) sort after versioned ones
+fn family_version(name: &str) -> Vec<u32> {
+    name.split('-')
+        .nth(1)
+        .and_then(|segment| segment.split('.').map(|n| n.parse().ok()).collect::<Option<Vec<u32>>>())
+        .unwrap_or_default()
+}
+
+/// Whether `pattern`'s segments appear contiguously among `segments`
+fn has_segments(segments: &[&str], pattern: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('-').collect();
+    segments.windows(pattern.len()).any(|window| window == pattern.as_slice())
+}
+
+impl ModelRanking {
+    fn tier(&self, segments: &[&str]) -> usize {
+        self.tiers.iter()
+            .enumerate()
+            .filter(|(_, tier)| has_segments(segments, tier))
+            .max_by_key(|(_, tier)| tier.split('-').count())
+            .map_or(usize::MAX, |(index, _)| index)
+    }
+
+    /// Best models first
+    pub fn sort(&self, models: &mut [ModelInfo]) {
+        models.sort_by_cached_key(|model| {
+            let segments: Vec<&str> = model.name.split('-').collect();
+            (
+                self.pinned.iter().position(|pinned| *pinned == model.name).unwrap_or(usize::MAX),
+                self.demoted.iter().any(|demoted| has_segments(&segments, demoted)),
+                Reverse(family_version(&model.name)),
+                self.tier(&segments),
+                model.name.clone(),
+            )
+        });
+    }
+}
+
+/// Gemini models supporting `generation_method` (default `generateContent`),
+/// best first according to the configured ranking
+#[tauri::command]
+pub(crate) async fn get_gemini_model_info(
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    generation_method: Option<String>,
+) -> Result<Vec<ModelInfo>, String> {
+    let method = generation_method.unwrap_or_else(|| "generateContent".to_string());
+    let mut models: Vec<ModelInfo> = GeminiProvider::new(http.client(), secrets.gemini_api_key()?)
+        .model_info()
+        .await?
+        .into_iter()
+        .filter(|model| model.supported_generation_methods.contains(&method))
+        .collect();
+    read_settings().model_ranking.sort(&mut models);
+    Ok(models)
+}
+
+/// Names of the generative Gemini models, best first
+#[tauri::command]
+pub(crate) async fn get_gemini_models_sorted(
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+) -> Result<Vec<String>, String> {
+    let models = get_gemini_model_info(http, secrets, None).await?;
+    Ok(models.into_iter().map(|model| model.name).collect())
+}
+
+/// Pin `model` to the top of the list (appended after earlier pins) or unpin
+/// it; returns the pinned models in order
+#[tauri::command]
+pub(crate) fn set_model_pinned(model: String, pinned: bool) -> Result<Vec<String>, String> {
+    let mut settings = read_settings();
+    let pins = &mut settings.model_ranking.pinned;
+    pins.retain(|name| *name != model);
+    if pinned {
+        pins.push(model);
+    }
+    write_settings(&settings)?;
+    Ok(settings.model_ranking.pinned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(models: &[ModelInfo]) -> Vec<&str> {
+        models.iter().map(|model| model.name.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_by_pin_stability_version_and_tier() {
+        let mut models: Vec<ModelInfo> = [
+            "gemini-1.5-pro",
+            "gemini-2.0-flash-lite",
+            "gemini-2.5-flash",
+            "gemini-exp-1206",
+            "gemini-3-pro-preview",
+            "gemini-2.5-pro",
+            "gemini-2.0-flash",
+        ].iter().map(|name| ModelInfo { name: name.to_string(), ..Default::default() }).collect();
+
+        let mut ranking = ModelRanking::default();
+        ranking.sort(&mut models);
+        assert_eq!(names(&models), vec![
+            "gemini-2.5-pro",
+            "gemini-2.5-flash",
+            "gemini-2.0-flash",
+            "gemini-2.0-flash-lite",
+            "gemini-1.5-pro",
+            "gemini-3-pro-preview",
+            "gemini-exp-1206",
+        ]);
+
+        ranking.pinned = vec!["gemini-2.0-flash-lite".to_string()];
+        ranking.sort(&mut models);
+        assert_eq!(names(&models)[..2], ["gemini-2.0-flash-lite", "gemini-2.5-pro"]);
+    }
+}
@@ -0,0 +1,77 @@
+// ============================================================================
+// OLLAMA MODEL MANAGEMENT
+// ============================================================================
+//
+// Pull, delete, inspect and copy local Ollama models, so the `/ai pull`
+// workflow from `.gemini/commands` works from the GUI without a terminal.
+// Pull progress is emitted on `ollama-pull` as Ollama reports it. Loaded
+// models can be listed and unloaded to free memory between swarm runs.
+
+use serde::Serialize;
+use tauri::{Emitter, State, Window};
+
+use crate::http::HttpState;
+use crate::providers::{OllamaModelDetails, OllamaProvider, PullProgress, RunningModel};
+
+const PULL_EVENT: &str = "ollama-pull";
+
+#[derive(Clone, Serialize)]
+struct PullPayload<'a> {
+    model: &'a str,
+    #[serde(flatten)]
+    progress: &'a PullProgress,
+}
+
+/// Download `model`, emitting `ollama-pull` events with `status` and, while
+/// layers download, `completed` / `total` bytes. Resolves once the pull succeeded.
+#[tauri::command]
+pub(crate) async fn pull_ollama_model(
+    window: Window,
+    http: State<'_, HttpState>,
+    endpoint: String,
+    model: String,
+) -> Result<(), String> {
+    let provider = OllamaProvider::new(http.client(), endpoint);
+    let mut on_progress = |progress: &PullProgress| {
+        let _ = window.emit(PULL_EVENT, PullPayload { model: &model, progress });
+    };
+    Ok(provider.pull(&model, &mut on_progress).await?)
+}
+
+#[tauri::command]
+pub(crate) async fn delete_ollama_model(http: State<'_, HttpState>, endpoint: String, model: String) -> Result<(), String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).delete_model(&model).await?)
+}
+
+/// Parameters, template, context length and quantization of a local model
+#[tauri::command]
+pub(crate) async fn show_ollama_model(
+    http: State<'_, HttpState>,
+    endpoint: String,
+    model: String,
+) -> Result<OllamaModelDetails, String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).show_model(&model).await?)
+}
+
+/// Copy `source` under a new name, e.g. before customising its Modelfile
+#[tauri::command]
+pub(crate) async fn copy_ollama_model(
+    http: State<'_, HttpState>,
+    endpoint: String,
+    source: String,
+    destination: String,
+) -> Result<(), String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).copy_model(&source, &destination).await?)
+}
+
+/// Models Ollama currently holds in memory, with their VRAM/RAM use and expiry
+#[tauri::command]
+pub(crate) async fn get_ollama_running(http: State<'_, HttpState>, endpoint: String) -> Result<Vec<RunningModel>, String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).running_models().await?)
+}
+
+/// Unload `model` immediately instead of waiting for its keep-alive to expire
+#[tauri::command]
+pub(crate) async fn unload_ollama_model(http: State<'_, HttpState>, endpoint: String, model: String) -> Result<(), String> {
+    Ok(OllamaProvider::new(http.client(), endpoint).unload_model(&model).await?)
+}
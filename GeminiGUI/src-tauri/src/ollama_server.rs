@@ -0,0 +1,587 @@
+// ============================================================================
+// OLLAMA SUPERVISOR
+// ============================================================================
+//
+// Replaces the fire-and-forget `start-ollama.ps1` launch. The supervisor owns
+// an `ollama serve` child process, polls `/api/version` to know when it is
+// up, restarts it with exponential backoff when it dies and reports every
+// state change on `ollama-status`. A server that is already answering (an
+// Ollama the user started) is used as-is and never killed. The child is
+// terminated when the app exits: asked to quit first (SIGTERM on Unix) so a
+// pull or model write can finish, killed only if it does not.
+//
+// The server listens where `ollama_endpoint` in the backend settings says,
+// else where `OLLAMA_HOST` says, else on Ollama's default port. The same
+// address is probed and handed to the child as `OLLAMA_HOST`.
+//
+// The binary is looked up the same way on every platform: the portable
+// `bin/` folder of the GeminiCLI root first, then PATH, then the usual
+// install locations. Like `start-ollama.ps1`, the `data/ollama/models` folder
+// of the GeminiCLI root always becomes `OLLAMA_MODELS` and is created if missing.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::process::{Child, Command};
+use tokio::sync::watch;
+
+use crate::http::HttpState;
+
+const STATUS_EVENT: &str = "ollama-status";
+
+/// Where the supervised server listens unless configured otherwise (Ollama's default)
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_PORT: u16 = 11434;
+
+const HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+/// Poll interval while waiting for a fresh child to answer
+const STARTUP_POLL: Duration = Duration::from_millis(500);
+/// A child that does not answer within this time is killed and restarted
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// Uptime after which earlier crashes no longer lengthen the backoff
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+/// Failed health checks in a row before a running child counts as hung
+const MAX_MISSES: u32 = 3;
+/// How long a child asked to quit gets before it is killed
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum OllamaStatus {
+    Stopped,
+    Starting { attempt: u32 },
+    /// Healthy; `pid` is None when the server was not started by us
+    Running { pid: Option<u32>, version: String },
+    /// The child died or never came up; the next start is in `retry_in_ms`
+    Crashed { message: String, retry_in_ms: u64 },
+    /// Gave up, e.g. because there is no binary to start
+    Failed { error: OllamaLaunchError },
+}
+
+/// Why the supervisor could not start a server
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum OllamaLaunchError {
+    /// No `ollama` binary in any of the `searched` locations
+    NotFound { searched: Vec<String>, message: String },
+    SpawnFailed { binary: String, message: String },
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+fn backoff(failures: u32) -> Duration {
+    BACKOFF_BASE.saturating_mul(1u32 << failures.saturating_sub(1).min(16)).min(BACKOFF_MAX)
+}
+
+const BINARY_NAME: &str = if cfg!(windows) { "ollama.exe" } else { "ollama" };
+
+/// A located Ollama binary and the portable models folder to use; the folder
+/// is None only when there is no GeminiCLI root at all
+#[derive(Debug, Clone, PartialEq)]
+struct OllamaInstall {
+    binary: PathBuf,
+    models_dir: Option<PathBuf>,
+}
+
+fn models_dir_in(root: &Path) -> PathBuf {
+    root.join("data").join("ollama").join("models")
+}
+
+/// Candidate GeminiCLI roots: next to the installed app, then (for `tauri dev`)
+/// the working directory and its parents
+fn project_roots() -> Vec<PathBuf> {
+    let mut roots = vec![crate::get_base_dir()];
+    if let Ok(cwd) = std::env::current_dir() {
+        roots.extend(cwd.ancestors().take(3).map(Path::to_path_buf));
+    }
+    roots
+}
+
+/// Where installers put Ollama when it is not on PATH (a GUI launched from
+/// Finder or a desktop entry often gets a minimal PATH)
+fn install_locations() -> Vec<PathBuf> {
+    let mut locations = Vec::new();
+    if cfg!(windows) {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            locations.push(PathBuf::from(local).join("Programs").join("Ollama").join(BINARY_NAME));
+        }
+    } else if cfg!(target_os = "macos") {
+        locations.push(PathBuf::from("/Applications/Ollama.app/Contents/Resources/ollama"));
+        locations.push(PathBuf::from("/opt/homebrew/bin/ollama"));
+        locations.push(PathBuf::from("/usr/local/bin/ollama"));
+    } else {
+        locations.push(PathBuf::from("/usr/local/bin/ollama"));
+        locations.push(PathBuf::from("/usr/bin/ollama"));
+    }
+    locations
+}
+
+/// First binary found in `roots`' `bin/`, then `path_dirs`, then `locations`.
+/// The models folder comes from the binary's root, else any root that has
+/// one, else the first root (it is created before the server starts).
+fn locate_in(roots: &[PathBuf], path_dirs: &[PathBuf], locations: &[PathBuf]) -> Result<OllamaInstall, OllamaLaunchError> {
+    let portable = roots.iter().map(|root| (Some(root), root.join("bin").join(BINARY_NAME)));
+    let installed = path_dirs.iter()
+        .map(|dir| dir.join(BINARY_NAME))
+        .chain(locations.iter().cloned())
+        .map(|path| (None, path));
+
+    let mut searched = Vec::new();
+    for (root, binary) in portable.chain(installed) {
+        if binary.is_file() {
+            let models_dir = root.map(|root| models_dir_in(root))
+                .or_else(|| roots.iter().map(|root| models_dir_in(root)).find(|dir| dir.is_dir()))
+                .or_else(|| roots.first().map(|root| models_dir_in(root)));
+            return Ok(OllamaInstall { binary, models_dir });
+        }
+        searched.push(binary.display().to_string());
+    }
+    Err(OllamaLaunchError::NotFound {
+        message: format!(
+            "Ollama is not installed: no {} in bin/, on PATH or in the default install locations. \
+             Install it from https://ollama.com or copy the binary into bin/.",
+            BINARY_NAME
+        ),
+        searched,
+    })
+}
+
+fn locate_ollama() -> Result<OllamaInstall, OllamaLaunchError> {
+    let path_dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    locate_in(&project_roots(), &path_dirs, &install_locations())
+}
+
+/// Where the server listens: `url` is probed, `host` is the child's `OLLAMA_HOST`
+#[derive(Debug, Clone, PartialEq)]
+struct ServerAddress {
+    url: String,
+    host: String,
+}
+
+/// Accepts what `OLLAMA_HOST` accepts: `host`, `host:port` or a URL
+fn server_address(configured: Option<&str>, ollama_host: Option<&str>) -> ServerAddress {
+    let raw = configured.into_iter().chain(ollama_host)
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_HOST);
+    let with_scheme = if raw.contains("://") { raw.to_string() } else { format!("http://{}", raw) };
+    let parsed = reqwest::Url::parse(&with_scheme).ok()
+        .and_then(|url| Some((url.scheme().to_string(), url.host_str()?.to_string(), url.port())));
+    let (scheme, host, port) = match parsed {
+        Some(parsed) => parsed,
+        None => ("http".to_string(), DEFAULT_HOST.to_string(), None),
+    };
+    let port = port.unwrap_or(DEFAULT_PORT);
+    // A server bound to every interface is reached over loopback
+    let reachable = match host.as_str() {
+        "0.0.0.0" => "127.0.0.1",
+        "[::]" => "[::1]",
+        host => host,
+    };
+    ServerAddress {
+        url: format!("{}://{}:{}", scheme, reachable, port),
+        host: format!("{}:{}", host, port),
+    }
+}
+
+/// Read on every check, so a changed address is picked up without restarting the app
+fn configured_address() -> ServerAddress {
+    let ollama_host = std::env::var("OLLAMA_HOST").ok()
+        .or_else(|| crate::read_env_file().ok()?.remove("OLLAMA_HOST"));
+    server_address(crate::settings::read_settings().ollama_endpoint.as_deref(), ollama_host.as_deref())
+}
+
+fn spawn_server(install: &OllamaInstall, address: &ServerAddress) -> Result<Child, OllamaLaunchError> {
+    let mut command = Command::new(&install.binary);
+    command.arg("serve")
+        .env("OLLAMA_HOST", &address.host)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(models_dir) = &install.models_dir {
+        std::fs::create_dir_all(models_dir).map_err(|e| OllamaLaunchError::SpawnFailed {
+            binary: install.binary.display().to_string(),
+            message: format!("Failed to create {}: {}", models_dir.display(), e),
+        })?;
+        command.env("OLLAMA_MODELS", models_dir);
+    }
+    #[cfg(windows)]
+    {
+        // CREATE_NO_WINDOW: no console flashing up behind the GUI
+        command.creation_flags(0x0800_0000);
+    }
+    command.spawn().map_err(|e| OllamaLaunchError::SpawnFailed {
+        binary: install.binary.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Ask `child` to quit and kill it if it has not within `grace`. Windows has
+/// no such request for a process without a console, so it is killed at once.
+async fn shutdown(mut child: Child, grace: Duration) {
+    #[cfg(unix)]
+    {
+        if let Some(pid) = child.id() {
+            // SAFETY: plain syscall; the child has not been reaped, so `pid` is still ours
+            unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+            if tokio::time::timeout(grace, child.wait()).await.is_ok() {
+                return;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = grace;
+    let _ = child.kill().await;
+}
+
+struct Shared {
+    child: Mutex<Option<Child>>,
+    status: Mutex<Option<OllamaStatus>>,
+    /// Whether supervision is wanted. A watch channel rather than a `Notify`:
+    /// it holds a state, not a permit, so a stop cannot leak into the next start.
+    active: watch::Sender<bool>,
+    /// Bumped on every start, so a loop left over from an earlier start exits
+    generation: AtomicU64,
+}
+
+impl Default for Shared {
+    fn default() -> Self {
+        Self {
+            child: Mutex::default(),
+            status: Mutex::default(),
+            active: watch::Sender::new(false),
+            generation: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Shared {
+    fn status(&self) -> OllamaStatus {
+        self.status.lock().unwrap().clone().unwrap_or(OllamaStatus::Stopped)
+    }
+
+    /// Record `status` and emit it if it changed
+    fn set_status(&self, app: &AppHandle, status: OllamaStatus) {
+        let mut current = self.status.lock().unwrap();
+        if current.as_ref() != Some(&status) {
+            let _ = app.emit(STATUS_EVENT, &status);
+            *current = Some(status);
+        }
+    }
+
+    fn child_pid(&self) -> Option<u32> {
+        self.child.lock().unwrap().as_ref().and_then(|child| child.id())
+    }
+
+    /// Forget the child if it has exited, describing how it ended
+    fn reap(&self) -> Option<String> {
+        let mut guard = self.child.lock().unwrap();
+        let exit = guard.as_mut()?.try_wait();
+        match exit {
+            Ok(None) => None,
+            Ok(Some(status)) => {
+                *guard = None;
+                Some(format!("Ollama exited with {}", status))
+            }
+            Err(e) => {
+                *guard = None;
+                Some(format!("Lost track of Ollama: {}", e))
+            }
+        }
+    }
+
+    /// Shut down the child we started, if any
+    async fn terminate(&self) {
+        let child = self.child.lock().unwrap().take();
+        if let Some(child) = child {
+            shutdown(child, SHUTDOWN_GRACE).await;
+        }
+    }
+
+    /// Whether the loop started as `generation` should keep going
+    fn is_current(&self, generation: u64) -> bool {
+        *self.active.borrow() && self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// Sleep for `duration`, waking early on stop; false if the loop should end
+    async fn sleep(&self, duration: Duration, generation: u64) -> bool {
+        let mut active = self.active.subscribe();
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = active.wait_for(|active| !*active) => {}
+        }
+        self.is_current(generation)
+    }
+}
+
+async fn server_version(app: &AppHandle, address: &ServerAddress) -> Option<String> {
+    let http = app.state::<HttpState>().client();
+    let res = http.get(format!("{}/api/version", address.url))
+        .timeout(HEALTH_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    if !res.status().is_success() {
+        return None;
+    }
+    res.json::<VersionResponse>().await.ok().map(|body| body.version)
+}
+
+async fn supervise(app: AppHandle, shared: Arc<Shared>, generation: u64) {
+    let mut failures: u32 = 0;
+    let mut misses: u32 = 0;
+    let mut spawned_at: Option<Instant> = None;
+    let mut healthy_since: Option<Instant> = None;
+
+    while shared.is_current(generation) {
+        let address = configured_address();
+        if let Some(version) = server_version(&app, &address).await {
+            spawned_at = None;
+            misses = 0;
+            let since = *healthy_since.get_or_insert_with(Instant::now);
+            if since.elapsed() >= STABLE_AFTER {
+                failures = 0;
+            }
+            shared.set_status(&app, OllamaStatus::Running { pid: shared.child_pid(), version });
+            if !shared.sleep(HEALTH_INTERVAL, generation).await {
+                break;
+            }
+            continue;
+        }
+        healthy_since = None;
+
+        let mut problem = shared.reap();
+        if problem.is_none() && shared.child_pid().is_some() {
+            // Our child is alive but not answering (yet): give a fresh one
+            // time to start and a running one a few chances before killing it
+            let (wait, patient) = match spawned_at {
+                Some(at) => (STARTUP_POLL, at.elapsed() < STARTUP_TIMEOUT),
+                None => {
+                    misses += 1;
+                    (HEALTH_INTERVAL, misses < MAX_MISSES)
+                }
+            };
+            if patient {
+                if !shared.sleep(wait, generation).await {
+                    break;
+                }
+                continue;
+            }
+            shared.terminate().await;
+            misses = 0;
+            problem = Some(if spawned_at.is_some() {
+                "Ollama did not answer in time".to_string()
+            } else {
+                "Ollama stopped answering".to_string()
+            });
+        }
+
+        if let Some(message) = problem {
+            failures += 1;
+            let wait = backoff(failures);
+            shared.set_status(&app, OllamaStatus::Crashed { message, retry_in_ms: wait.as_millis() as u64 });
+            if !shared.sleep(wait, generation).await {
+                break;
+            }
+        }
+
+        // Located again on every start, so installing Ollama meanwhile is picked up
+        match locate_ollama().and_then(|install| spawn_server(&install, &address)) {
+            Ok(child) => {
+                *shared.child.lock().unwrap() = Some(child);
+                spawned_at = Some(Instant::now());
+                shared.set_status(&app, OllamaStatus::Starting { attempt: failures + 1 });
+            }
+            Err(error) => {
+                shared.active.send_replace(false);
+                shared.set_status(&app, OllamaStatus::Failed { error });
+                return;
+            }
+        }
+    }
+
+    // A newer loop has taken over the child and the status
+    if shared.generation.load(Ordering::SeqCst) == generation {
+        shared.terminate().await;
+        shared.set_status(&app, OllamaStatus::Stopped);
+    }
+}
+
+/// Owns the supervised Ollama process (Tauri managed state)
+#[derive(Default)]
+pub(crate) struct OllamaSupervisor {
+    shared: Arc<Shared>,
+}
+
+impl OllamaSupervisor {
+    /// Start supervising unless already doing so
+    pub fn start(&self, app: &AppHandle) {
+        if self.shared.active.send_replace(true) {
+            return;
+        }
+        let generation = self.shared.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        tauri::async_runtime::spawn(supervise(app.clone(), self.shared.clone(), generation));
+    }
+
+    /// Stop supervising and shut down the child we started, if any
+    pub async fn stop(&self) {
+        self.shared.active.send_replace(false);
+        self.shared.terminate().await;
+    }
+
+    pub fn status(&self) -> OllamaStatus {
+        self.shared.status()
+    }
+}
+
+/// Start the supervisor (idempotent) and return the current status; later
+/// changes arrive as `ollama-status` events. Fails up front when no server is
+/// answering and there is no binary to start one.
+#[tauri::command]
+pub(crate) async fn start_ollama_server(
+    app: AppHandle,
+    supervisor: State<'_, OllamaSupervisor>,
+) -> Result<OllamaStatus, OllamaLaunchError> {
+    if server_version(&app, &configured_address()).await.is_none() {
+        locate_ollama()?;
+    }
+    supervisor.start(&app);
+    Ok(supervisor.status())
+}
+
+/// Stop supervising and shut down the server we started
+#[tauri::command]
+pub(crate) async fn stop_ollama_server(supervisor: State<'_, OllamaSupervisor>) -> Result<OllamaStatus, String> {
+    supervisor.stop().await;
+    Ok(supervisor.status())
+}
+
+#[tauri::command]
+pub(crate) fn get_ollama_status(supervisor: State<'_, OllamaSupervisor>) -> OllamaStatus {
+    supervisor.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(7), BACKOFF_MAX);
+        assert_eq!(backoff(u32::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn prefers_the_portable_binary_and_models_folder() {
+        let base = std::env::temp_dir().join(format!("geminigui-ollama-{}", std::process::id()));
+        let root = base.join("GeminiCLI");
+        let path_dir = base.join("usr-bin");
+        for dir in [root.join("bin"), root.join("data").join("ollama").join("models"), path_dir.clone()] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(path_dir.join(BINARY_NAME), b"").unwrap();
+
+        let roots = [base.join("missing"), root.clone()];
+        let path_dirs = [path_dir.clone()];
+        let found = locate_in(&roots, &path_dirs, &[]).unwrap();
+        assert_eq!(found, OllamaInstall {
+            binary: path_dir.join(BINARY_NAME),
+            models_dir: Some(root.join("data").join("ollama").join("models")),
+        });
+
+        // Without a models folder anywhere, the first root gets one
+        let bare = [base.join("bare"), base.join("other")];
+        assert_eq!(
+            locate_in(&bare, &path_dirs, &[]).unwrap().models_dir,
+            Some(base.join("bare").join("data").join("ollama").join("models")),
+        );
+
+        std::fs::write(root.join("bin").join(BINARY_NAME), b"").unwrap();
+        assert_eq!(locate_in(&roots, &path_dirs, &[]).unwrap().binary, root.join("bin").join(BINARY_NAME));
+
+        match locate_in(&[base.join("missing")], &[], &[base.join("nowhere")]) {
+            Err(OllamaLaunchError::NotFound { searched, .. }) => assert_eq!(searched.len(), 2),
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+        let _ = std::fs::remove_dir_all(base);
+    }
+
+    #[test]
+    fn address_comes_from_settings_then_ollama_host() {
+        let address = |configured, ollama_host| {
+            let ServerAddress { url, host } = server_address(configured, ollama_host);
+            (url, host)
+        };
+        let default = ("http://127.0.0.1:11434".to_string(), "127.0.0.1:11434".to_string());
+        assert_eq!(address(None, None), default);
+        assert_eq!(address(Some(" "), None), default);
+        assert_eq!(address(Some("http://localhost:11500/"), Some("0.0.0.0")),
+            ("http://localhost:11500".to_string(), "localhost:11500".to_string()));
+        assert_eq!(address(None, Some("0.0.0.0")),
+            ("http://127.0.0.1:11434".to_string(), "0.0.0.0:11434".to_string()));
+        assert_eq!(address(None, Some("[::]:8080")),
+            ("http://[::1]:8080".to_string(), "[::]:8080".to_string()));
+    }
+
+    #[tokio::test]
+    async fn a_stop_does_not_carry_over_to_the_next_start() {
+        let shared = Shared::default();
+        shared.active.send_replace(true);
+        shared.active.send_replace(false);
+        shared.active.send_replace(true);
+        let generation = shared.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let started = Instant::now();
+        assert!(shared.sleep(Duration::from_millis(50), generation).await);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn stop_wakes_a_sleeping_loop() {
+        let shared = Arc::new(Shared::default());
+        shared.active.send_replace(true);
+        let sleeper = shared.clone();
+        let sleeping = tokio::spawn(async move { sleeper.sleep(Duration::from_secs(30), 0).await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shared.active.send_replace(false);
+        let woke = tokio::time::timeout(Duration::from_secs(2), sleeping).await;
+        assert!(!woke.expect("sleep was not woken").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn shutdown_terminates_before_killing() {
+        // Exits on SIGTERM
+        let polite = Command::new("sleep").arg("30").spawn().unwrap();
+        let started = Instant::now();
+        shutdown(polite, Duration::from_secs(10)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Ignores SIGTERM, so it is killed once the grace period is over
+        let stubborn = Command::new("sh").args(["-c", "trap '' TERM; sleep 30"]).spawn().unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let started = Instant::now();
+        shutdown(stubborn, Duration::from_millis(200)).await;
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
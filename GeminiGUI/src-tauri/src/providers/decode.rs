@@ -0,0 +1,239 @@
+// Incremental decoders for streamed HTTP bodies. Network chunks can end in the
+// middle of a line or even a multi-byte UTF-8 sequence, so bytes are buffered
+// until a full line is available.
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+/// Splits a byte stream into lines, carrying incomplete lines across chunks
+#[derive(Default)]
+pub(crate) struct LineDecoder {
+    buf: Vec<u8>,
+    /// Length of the prefix of `buf` already known to hold no newline
+    scanned: usize,
+}
+
+impl LineDecoder {
+    /// Feed a chunk and return every line it completed (without `\n` / `\r\n`)
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<String>, String> {
+        self.buf.extend_from_slice(chunk);
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut result = Ok(());
+        // A long line arriving in small chunks is searched once, not per chunk
+        while let Some(offset) = self.buf[self.scanned..].iter().position(|&b| b == b'\n') {
+            let end = self.scanned + offset;
+            self.scanned = end + 1;
+            let line = Self::decode(&self.buf[start..end]);
+            start = end + 1;
+            match line {
+                Ok(line) => lines.push(line),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            self.scanned = self.buf.len();
+        }
+        self.buf.drain(..start);
+        self.scanned -= start;
+        result.map(|_| lines)
+    }
+
+    /// Flush a trailing line that was not terminated by a newline
+    pub fn finish(&mut self) -> Result<Option<String>, String> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        let raw = std::mem::take(&mut self.buf);
+        self.scanned = 0;
+        Self::decode(&raw).map(Some)
+    }
+
+    fn decode(raw: &[u8]) -> Result<String, String> {
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        String::from_utf8(raw.to_vec()).map_err(|e| format!("Invalid UTF-8 in stream: {}", e))
+    }
+}
+
+/// Decodes a `text/event-stream` body into the `data` payload of each event
+#[derive(Default)]
+pub(crate) struct SseDecoder {
+    lines: LineDecoder,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    /// Feed a chunk and return the data of every event it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<String>, String> {
+        let mut events = Vec::new();
+        for line in self.lines.push(chunk)? {
+            self.feed_line(line, &mut events);
+        }
+        Ok(events)
+    }
+
+    /// Flush the last event if the stream ended without a blank line
+    pub fn finish(&mut self) -> Result<Vec<String>, String> {
+        let mut events = Vec::new();
+        if let Some(line) = self.lines.finish()? {
+            self.feed_line(line, &mut events);
+        }
+        self.dispatch(&mut events);
+        Ok(events)
+    }
+
+    fn feed_line(&mut self, line: String, events: &mut Vec<String>) {
+        if line.is_empty() {
+            self.dispatch(events);
+            return;
+        }
+        // Lines starting with ':' are comments (keep-alives)
+        if line.starts_with(':') {
+            return;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line.as_str(), ""),
+        };
+        // `event`, `id` and `retry` carry nothing the providers use
+        if field == "data" {
+            self.data.push(value.to_string());
+        }
+    }
+
+    fn dispatch(&mut self, events: &mut Vec<String>) {
+        if !self.data.is_empty() {
+            events.push(self.data.join("\n"));
+            self.data.clear();
+        }
+    }
+}
+
+/// Decodes newline-delimited JSON (one object per line) into `T`
+pub(crate) struct NdjsonDecoder<T> {
+    lines: LineDecoder,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Default for NdjsonDecoder<T> {
+    fn default() -> Self {
+        Self {
+            lines: LineDecoder::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> NdjsonDecoder<T> {
+    /// Feed a chunk and return every object it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<T>, String> {
+        self.lines.push(chunk)?
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Self::parse(line))
+            .collect()
+    }
+
+    /// Flush a trailing object that was not terminated by a newline
+    pub fn finish(&mut self) -> Result<Option<T>, String> {
+        match self.lines.finish()? {
+            Some(line) if !line.trim().is_empty() => Self::parse(&line).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn parse(line: &str) -> Result<T, String> {
+        serde_json::from_str(line).map_err(|e| format!("Invalid JSON line in stream: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Chunk {
+        content: String,
+        done: bool,
+    }
+
+    const NDJSON: &str = concat!(
+        "{\"content\":\"Zażółć \",\"done\":false}\n",
+        "{\"content\":\"gęślą jaźń 🐺\",\"done\":false}\r\n",
+        "\n",
+        "{\"content\":\"\",\"done\":true}",
+    );
+
+    fn decode_in_pieces(body: &[u8], size: usize) -> Vec<Chunk> {
+        let mut decoder = NdjsonDecoder::<Chunk>::default();
+        let mut out = Vec::new();
+        for piece in body.chunks(size) {
+            out.extend(decoder.push(piece).unwrap());
+        }
+        out.extend(decoder.finish().unwrap());
+        out
+    }
+
+    #[test]
+    fn ndjson_survives_any_fragmentation() {
+        let whole = decode_in_pieces(NDJSON.as_bytes(), NDJSON.len());
+        assert_eq!(whole.len(), 3);
+        assert_eq!(whole[1].content, "gęślą jaźń 🐺");
+        assert!(whole[2].done);
+
+        // Every chunk size splits objects and multi-byte characters somewhere
+        for size in 1..=17 {
+            assert_eq!(decode_in_pieces(NDJSON.as_bytes(), size), whole, "chunk size {}", size);
+        }
+    }
+
+    #[test]
+    fn ndjson_reports_malformed_lines() {
+        let mut decoder = NdjsonDecoder::<Chunk>::default();
+        assert!(decoder.push(b"{\"content\":").unwrap().is_empty());
+        assert!(decoder.push(b" nope}\n").is_err());
+    }
+
+    #[test]
+    fn line_decoder_carries_split_utf8() {
+        let bytes = "ąę\nź".as_bytes();
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(&bytes[..1]).unwrap().is_empty());
+        assert_eq!(decoder.push(&bytes[1..6]).unwrap(), vec!["ąę".to_string()]);
+        assert!(decoder.push(&bytes[6..]).unwrap().is_empty());
+        assert_eq!(decoder.finish().unwrap(), Some("ź".to_string()));
+        assert_eq!(decoder.finish().unwrap(), None);
+    }
+
+    #[test]
+    fn line_decoder_handles_long_lines_in_small_chunks() {
+        let long = "x".repeat(100_000);
+        let body = format!("{}\nshort\n{}", long, long);
+        let mut decoder = LineDecoder::default();
+        let mut lines = Vec::new();
+        for piece in body.as_bytes().chunks(7) {
+            lines.extend(decoder.push(piece).unwrap());
+        }
+        assert_eq!(lines, vec![long.clone(), "short".to_string()]);
+        assert_eq!(decoder.finish().unwrap(), Some(long));
+    }
+
+    #[test]
+    fn sse_joins_data_lines_and_skips_comments() {
+        let body = b": keep-alive\ndata: {\"a\":1}\r\n\r\ndata: x\ndata: y\n\ndata: tail";
+        for size in 1..=body.len() {
+            let mut decoder = SseDecoder::default();
+            let mut events = Vec::new();
+            for piece in body.chunks(size) {
+                events.extend(decoder.push(piece).unwrap());
+            }
+            events.extend(decoder.finish().unwrap());
+            assert_eq!(events, vec!["{\"a\":1}", "x\ny", "tail"], "chunk size {}", size);
+        }
+    }
+}
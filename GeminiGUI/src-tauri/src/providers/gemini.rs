@@ -0,0 +1,622 @@
+use futures_util::StreamExt;
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpClient;
+use crate::redact;
+
+use super::decode::SseDecoder;
+use super::{
+    split_image, ChatMessage, ChatRequest, ChunkSink, LlmProvider, ProviderError, StreamOutcome, ToolCall,
+    ToolDeclaration, Usage,
+};
+
+pub(crate) const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// The key travels in a header so it never shows up in URLs, logs or error strings
+const API_KEY_HEADER: &str = "x-goog-api-key";
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiBlob {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFileData {
+    mime_type: String,
+    file_uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GeminiFunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GeminiFunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_data: Option<GeminiBlob>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_data: Option<GeminiFileData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_call: Option<GeminiFunctionCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_response: Option<GeminiFunctionResponse>,
+}
+
+impl GeminiPart {
+    fn text(text: &str) -> Self {
+        Self { text: Some(text.to_string()), ..Default::default() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GeminiContent {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    role: String,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+/// A tool result as a `functionResponse`, whose `response` must be an object
+fn function_response(m: &ChatMessage) -> GeminiPart {
+    let response = match serde_json::from_str(&m.content) {
+        Ok(serde_json::Value::Object(object)) => serde_json::Value::Object(object),
+        Ok(value) => serde_json::json!({ "result": value }),
+        Err(_) => serde_json::json!({ "result": m.content }),
+    };
+    GeminiPart {
+        function_response: Some(GeminiFunctionResponse { name: m.tool_name.clone().unwrap_or_default(), response }),
+        ..Default::default()
+    }
+}
+
+impl From<&ChatMessage> for GeminiContent {
+    fn from(m: &ChatMessage) -> Self {
+        if m.role == "tool" {
+            return Self { role: "user".to_string(), parts: vec![function_response(m)] };
+        }
+
+        let images = m.images.iter().flatten().map(|image| {
+            let (mime_type, data) = split_image(image);
+            GeminiPart {
+                inline_data: Some(GeminiBlob { mime_type: mime_type.to_string(), data: data.to_string() }),
+                ..Default::default()
+            }
+        });
+        let files = m.files.iter().flatten().map(|file| GeminiPart {
+            file_data: Some(GeminiFileData { mime_type: file.mime_type.clone(), file_uri: file.file_uri.clone() }),
+            ..Default::default()
+        });
+
+        let calls = m.tool_calls.iter().flatten().map(|call| GeminiPart {
+            function_call: Some(GeminiFunctionCall { name: call.name.clone(), args: call.arguments.clone() }),
+            ..Default::default()
+        });
+
+        let mut parts: Vec<GeminiPart> = images.chain(files).chain(calls).collect();
+        // Gemini rejects empty text parts, but a turn needs at least one part
+        if !m.content.is_empty() || parts.is_empty() {
+            parts.insert(0, GeminiPart::text(&m.content));
+        }
+
+        Self {
+            role: if m.role == "assistant" { "model".to_string() } else { "user".to_string() },
+            parts,
+        }
+    }
+}
+
+/// Sampling parameters, in the API's own field names (`topP`, `maxOutputTokens`, ...)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// e.g. `application/json`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+    /// Schema the JSON answer must follow; see `gemini_schema`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<serde_json::Value>,
+}
+
+/// Keywords Gemini's `responseSchema` (an OpenAPI subset) accepts
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "type", "format", "title", "description", "nullable", "enum", "properties", "required",
+    "propertyOrdering", "items", "minItems", "maxItems", "minimum", "maximum", "minLength",
+    "maxLength", "pattern", "anyOf",
+];
+
+/// Convert a JSON schema into Gemini's schema subset (used by `responseSchema`
+/// and function parameters), dropping keywords Gemini rejects (`$schema`,
+/// `additionalProperties`, ...). Validation against the full schema still
+/// happens on our side.
+pub(crate) fn gemini_schema(schema: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    let Value::Object(map) = schema else {
+        return schema.clone();
+    };
+    let converted = map.iter()
+        .filter(|(key, _)| SCHEMA_KEYWORDS.contains(&key.as_str()))
+        .map(|(key, value)| {
+            let value = match (key.as_str(), value) {
+                ("properties", Value::Object(props)) => Value::Object(
+                    props.iter().map(|(name, prop)| (name.clone(), gemini_schema(prop))).collect(),
+                ),
+                ("items", items) => gemini_schema(items),
+                ("anyOf", Value::Array(options)) => Value::Array(options.iter().map(gemini_schema).collect()),
+                _ => value.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect();
+    Value::Object(converted)
+}
+
+/// e.g. `{ "category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_ONLY_HIGH" }`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct SafetySetting {
+    pub category: String,
+    pub threshold: String,
+}
+
+/// Per-request Gemini options sent by the frontend alongside the provider kind
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct GeminiOptions {
+    pub generation_config: Option<GenerationConfig>,
+    pub safety_settings: Vec<SafetySetting>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<&'a GenerationConfig>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    safety_settings: &'a [SafetySetting],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<GeminiTool>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiTool {
+    function_declarations: Vec<ToolDeclaration>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<GeminiContent>,
+    /// Position among the `candidateCount` alternatives; absent for the first
+    #[serde(default)]
+    index: u32,
+}
+
+impl Candidate {
+    fn text(&self) -> String {
+        self.content.iter()
+            .flat_map(|c| c.parts.iter())
+            .filter_map(|p| p.text.as_deref())
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct GeminiError {
+    code: Option<u16>,
+    message: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+}
+
+/// One `generateContent` response, or one event of a `streamGenerateContent` stream
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    error: Option<GeminiError>,
+    /// Running totals; the last event of a stream has the final counts
+    usage_metadata: Option<UsageMetadata>,
+}
+
+impl GeminiResponse {
+    fn parse(json: &str) -> Result<Self, ProviderError> {
+        let response: GeminiResponse = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid Gemini response: {}", e))?;
+        match response.error {
+            Some(GeminiError { code: Some(code), message }) => Err(ProviderError::Status {
+                status: code,
+                message: format!("Gemini API Error: {} {}", code, message),
+            }),
+            Some(GeminiError { code: None, message }) => Err(format!("Gemini API Error: {}", message).into()),
+            None => Ok(response),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GeminiEmbedRequest {
+    model: String,
+    content: GeminiContent,
+}
+
+#[derive(Serialize)]
+struct GeminiBatchEmbedRequest {
+    requests: Vec<GeminiEmbedRequest>,
+}
+
+#[derive(Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct GeminiBatchEmbedResponse {
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+/// A model from `/v1beta/models`; read in the API's camelCase, sent to the
+/// frontend in snake_case
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, rename_all(deserialize = "camelCase"))]
+pub(crate) struct ModelInfo {
+    /// Without the `models/` prefix, e.g. `gemini-2.5-pro`
+    pub name: String,
+    pub display_name: String,
+    pub description: String,
+    pub version: String,
+    pub input_token_limit: u64,
+    pub output_token_limit: u64,
+    /// e.g. `generateContent`, `countTokens`, `embedContent`
+    pub supported_generation_methods: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiModelsPage {
+    #[serde(default)]
+    models: Vec<ModelInfo>,
+    next_page_token: Option<String>,
+}
+
+pub(crate) struct GeminiProvider {
+    http: HttpClient,
+    api_key: String,
+    options: GeminiOptions,
+}
+
+impl GeminiProvider {
+    pub fn new(http: HttpClient, api_key: String) -> Self {
+        redact::register_secret(&api_key);
+        Self { http, api_key, options: GeminiOptions::default() }
+    }
+
+    pub fn with_options(mut self, options: GeminiOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        self.http.get(url).header(API_KEY_HEADER, &self.api_key)
+    }
+
+    fn post(&self, url: &str) -> RequestBuilder {
+        self.http.post(url).header(API_KEY_HEADER, &self.api_key)
+    }
+
+    /// Every model available to the key, following `nextPageToken`
+    pub async fn model_info(&self) -> Result<Vec<ModelInfo>, ProviderError> {
+        let url = format!("{}/models", GEMINI_API_BASE);
+        let mut models = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut request = self.get(&url).query(&[("pageSize", "1000")]);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+            let res = self.http.send(request)
+                .await?;
+
+            if !res.status().is_success() {
+                return Err(ProviderError::status("Gemini", res.status()));
+            }
+
+            let page: GeminiModelsPage = res.json().await.map_err(|e| e.to_string())?;
+            models.extend(page.models.into_iter().map(|m| ModelInfo {
+                name: m.name.trim_start_matches("models/").to_string(),
+                ..m
+            }));
+            match page.next_page_token.filter(|token| !token.is_empty()) {
+                Some(token) => page_token = Some(token),
+                None => return Ok(models),
+            }
+        }
+    }
+
+    /// Forward the text of the first candidate in one SSE event and collect
+    /// its function calls and usage. With `candidateCount > 1` the other
+    /// alternatives are dropped rather than interleaved into one text.
+    fn emit_event(event: &str, on_chunk: &mut ChunkSink<'_>, outcome: &mut StreamOutcome) -> Result<(), ProviderError> {
+        let response = GeminiResponse::parse(event)?;
+        for candidate in response.candidates.into_iter().filter(|c| c.index == 0) {
+            let text = candidate.text();
+            if !text.is_empty() {
+                on_chunk(&text)?;
+            }
+            let calls = candidate.content.into_iter().flat_map(|c| c.parts).filter_map(|p| p.function_call);
+            outcome.tool_calls.extend(calls.map(|call| ToolCall {
+                id: String::new(),
+                name: call.name,
+                arguments: call.args,
+            }));
+        }
+        if let Some(u) = response.usage_metadata {
+            outcome.usage = Some(Usage {
+                prompt_tokens: u.prompt_token_count,
+                completion_tokens: u.candidates_token_count,
+            });
+        }
+        Ok(())
+    }
+
+    /// `system` messages become the system instruction instead of fake user turns
+    fn request_body<'a>(&'a self, request: &ChatRequest) -> GeminiRequest<'a> {
+        let (system, turns): (Vec<&ChatMessage>, Vec<&ChatMessage>) =
+            request.messages.iter().partition(|m| m.role == "system");
+
+        let system_instruction = (!system.is_empty()).then(|| GeminiContent {
+            role: String::new(),
+            parts: system.into_iter().map(|m| GeminiPart::text(&m.content)).collect(),
+        });
+
+        GeminiRequest {
+            system_instruction,
+            contents: turns.into_iter().map(GeminiContent::from).collect(),
+            generation_config: self.options.generation_config.as_ref(),
+            safety_settings: &self.options.safety_settings,
+            tools: if request.tools.is_empty() {
+                Vec::new()
+            } else {
+                vec![GeminiTool {
+                    function_declarations: request.tools.iter().map(|tool| ToolDeclaration {
+                        parameters: tool.parameters.as_ref().map(gemini_schema),
+                        ..tool.clone()
+                    }).collect(),
+                }]
+            },
+        }
+    }
+}
+
+impl LlmProvider for GeminiProvider {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<String, ProviderError> {
+        let url = format!("{}/models/{}:generateContent", GEMINI_API_BASE, request.model);
+        let res = self.http.send_idempotent(self.post(&url).json(&self.request_body(request)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Gemini", res.status()));
+        }
+
+        let body = res.text().await.map_err(|e| e.to_string())?;
+        let response = GeminiResponse::parse(&body)?;
+        Ok(response.candidates.iter().find(|c| c.index == 0).map(Candidate::text).unwrap_or_default())
+    }
+
+    async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError> {
+        let url = format!("{}/models/{}:streamGenerateContent?alt=sse", GEMINI_API_BASE, request.model);
+        let res = self.http.send_idempotent(self.post(&url).json(&self.request_body(request)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Gemini", res.status()));
+        }
+
+        let mut stream = res.bytes_stream();
+        let mut decoder = SseDecoder::default();
+        let mut outcome = StreamOutcome::default();
+        while let Some(item) = stream.next().await {
+            let chunk = item.map_err(|e| e.to_string())?;
+            for event in decoder.push(&chunk)? {
+                Self::emit_event(&event, on_chunk, &mut outcome)?;
+            }
+        }
+        for event in decoder.finish()? {
+            Self::emit_event(&event, on_chunk, &mut outcome)?;
+        }
+
+        Ok(outcome)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        Ok(self.model_info().await?.into_iter().map(|m| m.name).collect())
+    }
+
+    async fn embed(&self, model: &str, input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let url = format!("{}/models/{}:batchEmbedContents", GEMINI_API_BASE, model);
+        let body = GeminiBatchEmbedRequest {
+            requests: input.iter().map(|text| GeminiEmbedRequest {
+                model: format!("models/{}", model),
+                content: GeminiContent {
+                    role: "user".to_string(),
+                    parts: vec![GeminiPart::text(text)],
+                },
+            }).collect(),
+        };
+
+        let res = self.http.send_idempotent(self.post(&url).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Gemini", res.status()));
+        }
+
+        let body: GeminiBatchEmbedResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.embeddings.into_iter().map(|e| e.values).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::FileRef;
+
+    #[test]
+    fn system_messages_become_the_system_instruction() {
+        let provider = GeminiProvider::new(HttpClient::new(reqwest::Client::new(), Default::default()), String::new())
+            .with_options(GeminiOptions {
+                generation_config: Some(GenerationConfig { temperature: Some(0.5), top_k: Some(40), ..Default::default() }),
+                safety_settings: Vec::new(),
+            });
+        let request = ChatRequest {
+            model: "gemini-pro".to_string(),
+            messages: vec![ChatMessage::new("system", "You are Geralt."), ChatMessage::new("user", "Hi"), ChatMessage::new("assistant", "Hmm.")],
+            tools: Vec::new(),
+        };
+
+        let body = serde_json::to_value(provider.request_body(&request)).unwrap();
+        assert_eq!(body, serde_json::json!({
+            "systemInstruction": { "parts": [{ "text": "You are Geralt." }] },
+            "contents": [
+                { "role": "user", "parts": [{ "text": "Hi" }] },
+                { "role": "model", "parts": [{ "text": "Hmm." }] }
+            ],
+            "generationConfig": { "temperature": 0.5, "topK": 40 }
+        }));
+    }
+
+    #[test]
+    fn only_the_first_candidate_is_streamed() {
+        let events = [
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hel"}]}},{"content":{"role":"model","parts":[{"text":"Bon"}]},"index":1}]}"#,
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"jour"}]},"index":1},{"content":{"role":"model","parts":[{"text":"lo"}]},"index":0}]}"#,
+        ];
+        let mut seen = String::new();
+        let mut sink = |chunk: &str| -> Result<(), String> {
+            seen.push_str(chunk);
+            Ok(())
+        };
+        let mut outcome = StreamOutcome::default();
+        for event in events {
+            GeminiProvider::emit_event(event, &mut sink, &mut outcome).unwrap();
+        }
+        assert_eq!(seen, "Hello");
+    }
+
+    #[test]
+    fn function_calls_round_trip() {
+        let event = r#"{"candidates":[{"content":{"role":"model","parts":[{"functionCall":{"name":"read_file","args":{"path":"a.txt"}}}]}}]}"#;
+        let mut sink = |_: &str| -> Result<(), String> { Ok(()) };
+        let mut outcome = StreamOutcome::default();
+        GeminiProvider::emit_event(event, &mut sink, &mut outcome).unwrap();
+        assert_eq!(outcome.tool_calls[0].name, "read_file");
+
+        let mut call = ChatMessage::new("assistant", "");
+        call.tool_calls = Some(outcome.tool_calls);
+        let mut result = ChatMessage::new("tool", "hello");
+        result.tool_name = Some("read_file".to_string());
+
+        let contents = serde_json::to_value([GeminiContent::from(&call), GeminiContent::from(&result)]).unwrap();
+        assert_eq!(contents, serde_json::json!([
+            { "role": "model", "parts": [{ "functionCall": { "name": "read_file", "args": { "path": "a.txt" } } }] },
+            { "role": "user", "parts": [{ "functionResponse": { "name": "read_file", "response": { "result": "hello" } } }] }
+        ]));
+    }
+
+    #[test]
+    fn response_schema_drops_unsupported_keywords() {
+        let schema = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "steps": { "type": "array", "items": { "type": "string", "$comment": "x" } }
+            },
+            "required": ["steps"]
+        });
+        assert_eq!(gemini_schema(&schema), serde_json::json!({
+            "type": "object",
+            "properties": { "steps": { "type": "array", "items": { "type": "string" } } },
+            "required": ["steps"]
+        }));
+    }
+
+    #[test]
+    fn images_and_files_become_parts() {
+        let mut msg = ChatMessage::new("user", "What is on screen?");
+        msg.images = Some(vec!["data:image/jpeg;base64,/9j/AAA".to_string(), "iVBORw0KGgo".to_string()]);
+        msg.files = Some(vec![FileRef { mime_type: "application/pdf".to_string(), file_uri: "files/abc".to_string() }]);
+
+        let content = serde_json::to_value(GeminiContent::from(&msg)).unwrap();
+        assert_eq!(content, serde_json::json!({
+            "role": "user",
+            "parts": [
+                { "text": "What is on screen?" },
+                { "inlineData": { "mimeType": "image/jpeg", "data": "/9j/AAA" } },
+                { "inlineData": { "mimeType": "image/png", "data": "iVBORw0KGgo" } },
+                { "fileData": { "mimeType": "application/pdf", "fileUri": "files/abc" } }
+            ]
+        }));
+    }
+
+    #[test]
+    fn model_info_reads_camel_case_and_writes_snake_case() {
+        let page: GeminiModelsPage = serde_json::from_value(serde_json::json!({
+            "models": [{
+                "name": "models/gemini-2.5-pro",
+                "displayName": "Gemini 2.5 Pro",
+                "inputTokenLimit": 1048576,
+                "outputTokenLimit": 65536,
+                "supportedGenerationMethods": ["generateContent", "countTokens"]
+            }],
+            "nextPageToken": "abc"
+        })).unwrap();
+
+        assert_eq!(page.next_page_token.as_deref(), Some("abc"));
+        let model = serde_json::to_value(&page.models[0]).unwrap();
+        assert_eq!(model["display_name"], "Gemini 2.5 Pro");
+        assert_eq!(model["input_token_limit"], 1048576);
+        assert_eq!(model["supported_generation_methods"][0], "generateContent");
+    }
+}
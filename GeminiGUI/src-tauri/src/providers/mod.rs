@@ -0,0 +1,348 @@
+// ============================================================================
+// LLM PROVIDERS
+// ============================================================================
+//
+// Every chat backend (Ollama, Gemini, OpenAI-compatible servers) implements `LlmProvider`, so the
+// Tauri commands in lib.rs only deal with `ChatRequest` and text chunks and
+// never touch a backend's wire format directly.
+
+mod decode;
+mod gemini;
+mod ollama;
+mod openai;
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpClient;
+use crate::redact;
+use crate::secrets::SecretStore;
+
+pub(crate) use gemini::{GeminiOptions, GeminiProvider, ModelInfo};
+use gemini::{gemini_schema, GEMINI_API_BASE};
+pub(crate) use ollama::{OllamaModelDetails, OllamaOptions, OllamaProvider, PullProgress, RunningModel};
+pub(crate) use openai::OpenAiProvider;
+
+/// Backend-neutral chat message, as sent by the frontend
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct ChatMessage {
+    pub role: String,
+    pub content: String,
+    /// Base64 images, bare or as `data:image/png;base64,...` URLs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    /// Files already uploaded to the provider (Gemini File API); ignored by Ollama
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<FileRef>>,
+    /// Tools an `assistant` turn asked to run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// For `tool` turns: the tool whose result `content` holds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// For `tool` turns: the `ToolCall::id` being answered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    /// Plain text message
+    pub fn new(role: &str, content: impl Into<String>) -> Self {
+        Self {
+            role: role.to_string(),
+            content: content.into(),
+            images: None,
+            files: None,
+            tool_calls: None,
+            tool_name: None,
+            tool_call_id: None,
+        }
+    }
+}
+
+/// A tool the model may call; `parameters` is a JSON schema for its arguments
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ToolDeclaration {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// A model's request to run a tool. The ID is assigned by the backend when
+/// the call is emitted; providers leave it empty.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ToolCall {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+/// Reference to an uploaded file, e.g. `https://generativelanguage.googleapis.com/v1beta/files/abc`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct FileRef {
+    pub mime_type: String,
+    pub file_uri: String,
+}
+
+/// Split an image into its MIME type and bare base64 data. Data URLs carry
+/// their type; bare base64 is sniffed from the magic bytes (PNG if unknown).
+pub(crate) fn split_image(image: &str) -> (&str, &str) {
+    if let Some((header, data)) = image.strip_prefix("data:").and_then(|rest| rest.split_once(',')) {
+        return (header.trim_end_matches(";base64"), data);
+    }
+    let mime_type = match image.get(..4) {
+        Some("/9j/") => "image/jpeg",
+        Some("R0lG") => "image/gif",
+        Some("UklG") => "image/webp",
+        _ => "image/png",
+    };
+    (mime_type, image)
+}
+
+/// Everything a provider needs to run a single completion
+#[derive(Debug, Clone)]
+pub(crate) struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub tools: Vec<ToolDeclaration>,
+}
+
+/// What a finished stream produced besides its text
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct StreamOutcome {
+    /// Token counts, if the backend reported them
+    pub usage: Option<Usage>,
+    /// Tools the model wants run before it can continue
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// Token counts reported by a backend for one completion
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Failure of a provider call. HTTP status and connection failures are kept
+/// apart from the rest so callers can tell whether another model is worth trying.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ProviderError {
+    Status { status: u16, message: String },
+    /// The server could not be reached at all (refused, DNS, TLS handshake)
+    Unreachable(String),
+    Other(String),
+}
+
+impl ProviderError {
+    pub fn status(backend: &str, status: reqwest::StatusCode) -> Self {
+        ProviderError::Status {
+            status: status.as_u16(),
+            message: format!("{} API Error: {}", backend, status),
+        }
+    }
+
+    /// Rate limits, server-side failures and unreachable servers (e.g. Ollama
+    /// not running), which another model may not hit
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ProviderError::Status { status, .. } => *status == 429 || *status >= 500,
+            ProviderError::Unreachable(_) => true,
+            ProviderError::Other(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Status { message, .. } => f.write_str(message),
+            ProviderError::Unreachable(message) => f.write_str(message),
+            ProviderError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for ProviderError {
+    fn from(message: String) -> Self {
+        ProviderError::Other(message)
+    }
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() {
+            ProviderError::Unreachable(err.to_string())
+        } else {
+            ProviderError::Other(err.to_string())
+        }
+    }
+}
+
+/// Errors leave the backend as strings, so this is where secrets get scrubbed
+impl From<ProviderError> for String {
+    fn from(err: ProviderError) -> Self {
+        redact::redact(&err.to_string())
+    }
+}
+
+/// Receives streamed text chunks; returning an error aborts the stream
+pub(crate) type ChunkSink<'a> = dyn FnMut(&str) -> Result<(), String> + Send + 'a;
+
+pub(crate) trait LlmProvider {
+    /// Short backend name reported to the frontend, e.g. `ollama`
+    fn name(&self) -> &'static str;
+
+    /// Run a completion and return the full answer
+    async fn chat(&self, request: &ChatRequest) -> Result<String, ProviderError>;
+
+    /// Run a completion, handing each text chunk to `on_chunk` as it arrives
+    async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError>;
+
+    /// List model names usable with `chat` / `stream`
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError>;
+
+    /// Embed each input text, returning one vector per input
+    async fn embed(&self, model: &str, input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError>;
+}
+
+/// Provider selection as sent by the frontend, e.g.
+/// `{ "kind": "ollama", "endpoint": "http://localhost:11434" }`.
+/// API keys are never part of it; they are looked up in the secret store.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum ProviderConfig {
+    Ollama {
+        endpoint: String,
+        #[serde(default)]
+        options: Option<OllamaOptions>,
+    },
+    Gemini {
+        #[serde(default)]
+        options: GeminiOptions,
+    },
+    /// `{ "kind": "openai", "base_url": "http://localhost:8080", "api_key_secret": "LMSTUDIO_KEY" }`
+    OpenAi {
+        base_url: String,
+        /// Name of the secret holding the bearer key; servers without auth omit it
+        #[serde(default)]
+        api_key_secret: Option<String>,
+        /// Set by `with_json_schema`; not accepted from the frontend
+        #[serde(skip)]
+        json_schema: Option<serde_json::Value>,
+    },
+}
+
+impl ProviderConfig {
+    /// Base URL of the server this config talks to
+    pub fn endpoint(&self) -> &str {
+        match self {
+            ProviderConfig::Ollama { endpoint, .. } => endpoint,
+            ProviderConfig::Gemini { .. } => GEMINI_API_BASE,
+            ProviderConfig::OpenAi { base_url, .. } => base_url,
+        }
+    }
+
+    /// Ask the backend for JSON following `schema`: Gemini's `responseSchema`,
+    /// Ollama's `format` or OpenAI's `response_format`
+    pub fn with_json_schema(self, schema: &serde_json::Value) -> Self {
+        match self {
+            ProviderConfig::Ollama { endpoint, options } => {
+                let mut options = options.unwrap_or_default();
+                options.format = Some(schema.clone());
+                ProviderConfig::Ollama { endpoint, options: Some(options) }
+            }
+            ProviderConfig::Gemini { mut options } => {
+                let config = options.generation_config.get_or_insert_with(Default::default);
+                config.response_mime_type = Some("application/json".to_string());
+                config.response_schema = Some(gemini_schema(schema));
+                ProviderConfig::Gemini { options }
+            }
+            ProviderConfig::OpenAi { base_url, api_key_secret, .. } => {
+                ProviderConfig::OpenAi { base_url, api_key_secret, json_schema: Some(schema.clone()) }
+            }
+        }
+    }
+
+    /// Build the provider that will serve `model`; Ollama options are completed
+    /// from the model's preset in settings
+    pub fn build(self, http: HttpClient, secrets: &SecretStore, model: &str) -> Result<Provider, String> {
+        Ok(match self {
+            ProviderConfig::Ollama { endpoint, options } => {
+                let options = crate::settings::ollama_options(model, options);
+                Provider::Ollama(OllamaProvider::new(http, endpoint).with_options(options))
+            }
+            ProviderConfig::Gemini { options } => {
+                Provider::Gemini(GeminiProvider::new(http, secrets.gemini_api_key()?).with_options(options))
+            }
+            ProviderConfig::OpenAi { base_url, api_key_secret, json_schema } => {
+                let api_key = match api_key_secret {
+                    Some(name) => Some(secrets.get(&name)?.ok_or_else(|| format!("Secret {} is not set", name))?),
+                    None => None,
+                };
+                Provider::OpenAi(OpenAiProvider::new(http, base_url, api_key).with_json_schema(json_schema))
+            }
+        })
+    }
+}
+
+/// One entry of a fallback chain: a provider plus the model to ask it for
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct ModelTarget {
+    pub provider: ProviderConfig,
+    pub model: String,
+}
+
+/// Concrete provider picked at runtime. Dispatching through an enum (rather
+/// than `dyn LlmProvider`) keeps the command futures `Send`.
+pub(crate) enum Provider {
+    Ollama(OllamaProvider),
+    Gemini(GeminiProvider),
+    OpenAi(OpenAiProvider),
+}
+
+impl LlmProvider for Provider {
+    fn name(&self) -> &'static str {
+        match self {
+            Provider::Ollama(p) => p.name(),
+            Provider::Gemini(p) => p.name(),
+            Provider::OpenAi(p) => p.name(),
+        }
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<String, ProviderError> {
+        match self {
+            Provider::Ollama(p) => p.chat(request).await,
+            Provider::Gemini(p) => p.chat(request).await,
+            Provider::OpenAi(p) => p.chat(request).await,
+        }
+    }
+
+    async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError> {
+        match self {
+            Provider::Ollama(p) => p.stream(request, on_chunk).await,
+            Provider::Gemini(p) => p.stream(request, on_chunk).await,
+            Provider::OpenAi(p) => p.stream(request, on_chunk).await,
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        match self {
+            Provider::Ollama(p) => p.list_models().await,
+            Provider::Gemini(p) => p.list_models().await,
+            Provider::OpenAi(p) => p.list_models().await,
+        }
+    }
+
+    async fn embed(&self, model: &str, input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        match self {
+            Provider::Ollama(p) => p.embed(model, input).await,
+            Provider::Gemini(p) => p.embed(model, input).await,
+            Provider::OpenAi(p) => p.embed(model, input).await,
+        }
+    }
+}
@@ -0,0 +1,673 @@
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpClient;
+
+use super::decode::NdjsonDecoder;
+use super::{
+    split_image, ChatMessage, ChatRequest, ChunkSink, LlmProvider, ProviderError, StreamOutcome, ToolCall,
+    ToolDeclaration, Usage,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OllamaFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OllamaToolCall {
+    function: OllamaFunctionCall,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct OllamaMessage {
+    role: String,
+    #[serde(default)]
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    images: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<OllamaToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_name: Option<String>,
+}
+
+impl From<&ChatMessage> for OllamaMessage {
+    fn from(m: &ChatMessage) -> Self {
+        Self {
+            role: m.role.clone(),
+            content: m.content.clone(),
+            // Ollama wants bare base64, without a data URL prefix
+            images: m.images.as_ref().map(|images| {
+                images.iter().map(|image| split_image(image).1.to_string()).collect()
+            }),
+            tool_calls: m.tool_calls.as_ref().map(|calls| {
+                calls.iter().map(|call| OllamaToolCall {
+                    function: OllamaFunctionCall { name: call.name.clone(), arguments: call.arguments.clone() },
+                }).collect()
+            }),
+            tool_name: m.tool_name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OllamaTool<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: &'a ToolDeclaration,
+}
+
+/// How long Ollama keeps the model loaded: seconds (`0` unloads, negative
+/// keeps it forever) or a duration string such as `"10m"`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum KeepAlive {
+    Seconds(i64),
+    Duration(String),
+}
+
+/// Generation options for `/api/chat`. Unset fields fall back to the model's
+/// preset from settings, then to Ollama's own defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub(crate) struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<KeepAlive>,
+    /// `"json"`, or a JSON schema the answer must follow
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+}
+
+impl OllamaOptions {
+    /// These options, with unset fields taken from `preset`
+    pub fn or(self, preset: &OllamaOptions) -> OllamaOptions {
+        OllamaOptions {
+            num_ctx: self.num_ctx.or(preset.num_ctx),
+            temperature: self.temperature.or(preset.temperature),
+            seed: self.seed.or(preset.seed),
+            keep_alive: self.keep_alive.or_else(|| preset.keep_alive.clone()),
+            format: self.format.or_else(|| preset.format.clone()),
+        }
+    }
+}
+
+/// The model parameters Ollama expects under `options`
+#[derive(Serialize)]
+struct OllamaModelOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<OllamaModelOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<&'a KeepAlive>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'a serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OllamaTool<'a>>,
+}
+
+#[derive(Deserialize)]
+struct OllamaChatResponse {
+    message: OllamaMessage,
+}
+
+/// One line of a streaming `/api/chat` response. Ollama reports failures
+/// mid-stream as `{"error": "..."}` instead of a message.
+#[derive(Deserialize)]
+struct OllamaChatChunk {
+    message: Option<OllamaMessage>,
+    error: Option<String>,
+    /// Token counts, present on the final (`done: true`) line
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+}
+
+impl OllamaChatChunk {
+    /// Send the chunk's text to `on_chunk` and collect its tool calls and usage
+    fn forward(self, on_chunk: &mut ChunkSink<'_>, outcome: &mut StreamOutcome) -> Result<(), ProviderError> {
+        if let Some(err) = self.error {
+            return Err(format!("Ollama API Error: {}", err).into());
+        }
+        if let Some(message) = self.message {
+            if !message.content.is_empty() {
+                on_chunk(&message.content)?;
+            }
+            outcome.tool_calls.extend(message.tool_calls.into_iter().flatten().map(|call| ToolCall {
+                id: String::new(),
+                name: call.function.name,
+                arguments: call.function.arguments,
+            }));
+        }
+        if self.prompt_eval_count.is_some() || self.eval_count.is_some() {
+            outcome.usage = Some(Usage {
+                prompt_tokens: self.prompt_eval_count.unwrap_or(0),
+                completion_tokens: self.eval_count.unwrap_or(0),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct OllamaModel {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OllamaTagsResponse {
+    models: Vec<OllamaModel>,
+}
+
+/// Progress of `/api/pull`: a status line, with byte counts while a layer
+/// (`digest`) downloads. The last status of a successful pull is `"success"`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct PullProgress {
+    #[serde(default)]
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+/// One line of a streaming `/api/pull` response
+#[derive(Deserialize)]
+struct OllamaPullChunk {
+    error: Option<String>,
+    #[serde(flatten)]
+    progress: PullProgress,
+}
+
+#[derive(Serialize)]
+struct OllamaModelRequest<'a> {
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct OllamaCopyRequest<'a> {
+    source: &'a str,
+    destination: &'a str,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct OllamaShowDetails {
+    format: String,
+    family: String,
+    parameter_size: String,
+    quantization_level: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaShowResponse {
+    #[serde(default)]
+    parameters: String,
+    #[serde(default)]
+    template: String,
+    #[serde(default)]
+    details: OllamaShowDetails,
+    #[serde(default)]
+    model_info: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    capabilities: Vec<String>,
+}
+
+/// What `/api/show` says about a local model
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct OllamaModelDetails {
+    pub name: String,
+    pub family: String,
+    pub format: String,
+    pub parameter_size: String,
+    pub quantization_level: String,
+    /// Context window the model was trained with, from `model_info`
+    pub context_length: Option<u64>,
+    /// Modelfile `PARAMETER` lines, e.g. `num_ctx 8192`
+    pub parameters: String,
+    pub template: String,
+    /// e.g. `completion`, `tools`, `vision` (newer Ollama versions only)
+    pub capabilities: Vec<String>,
+}
+
+impl OllamaShowResponse {
+    fn into_details(self, name: &str) -> OllamaModelDetails {
+        // Keys are prefixed with the architecture, e.g. `llama.context_length`
+        let context_length = self.model_info.get("general.architecture")
+            .and_then(|arch| arch.as_str())
+            .and_then(|arch| self.model_info.get(&format!("{}.context_length", arch)))
+            .and_then(|value| value.as_u64());
+        OllamaModelDetails {
+            name: name.to_string(),
+            family: self.details.family,
+            format: self.details.format,
+            parameter_size: self.details.parameter_size,
+            quantization_level: self.details.quantization_level,
+            context_length,
+            parameters: self.parameters,
+            template: self.template,
+            capabilities: self.capabilities,
+        }
+    }
+}
+
+/// A model currently loaded by Ollama (`/api/ps`)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct RunningModel {
+    pub name: String,
+    /// Bytes in use, in total and on the GPU; the rest sits in system RAM
+    pub size: u64,
+    pub size_vram: u64,
+    pub size_ram: u64,
+    /// When Ollama unloads the model unless it is used again (RFC 3339)
+    pub expires_at: Option<String>,
+    pub parameter_size: String,
+    pub quantization_level: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaRunningModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    size_vram: u64,
+    expires_at: Option<String>,
+    #[serde(default)]
+    details: OllamaShowDetails,
+}
+
+impl From<OllamaRunningModel> for RunningModel {
+    fn from(m: OllamaRunningModel) -> Self {
+        RunningModel {
+            name: m.name,
+            size: m.size,
+            size_vram: m.size_vram,
+            size_ram: m.size.saturating_sub(m.size_vram),
+            expires_at: m.expires_at,
+            parameter_size: m.details.parameter_size,
+            quantization_level: m.details.quantization_level,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OllamaPsResponse {
+    #[serde(default)]
+    models: Vec<OllamaRunningModel>,
+}
+
+#[derive(Serialize)]
+struct OllamaUnloadRequest<'a> {
+    model: &'a str,
+    keep_alive: KeepAlive,
+}
+
+#[derive(Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+pub(crate) struct OllamaProvider {
+    http: HttpClient,
+    endpoint: String,
+    options: OllamaOptions,
+}
+
+impl OllamaProvider {
+    pub fn new(http: HttpClient, endpoint: String) -> Self {
+        Self {
+            http,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            options: OllamaOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: OllamaOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.endpoint, path)
+    }
+
+    fn chat_body<'a>(&'a self, request: &'a ChatRequest, stream: bool) -> OllamaChatRequest<'a> {
+        let o = &self.options;
+        let has_model_options = o.num_ctx.is_some() || o.temperature.is_some() || o.seed.is_some();
+        OllamaChatRequest {
+            model: &request.model,
+            messages: request.messages.iter().map(OllamaMessage::from).collect(),
+            stream,
+            options: has_model_options.then_some(OllamaModelOptions {
+                num_ctx: o.num_ctx,
+                temperature: o.temperature,
+                seed: o.seed,
+            }),
+            keep_alive: o.keep_alive.as_ref(),
+            format: o.format.as_ref(),
+            tools: request.tools.iter().map(|tool| OllamaTool { kind: "function", function: tool }).collect(),
+        }
+    }
+
+    /// Download `model`, reporting each progress line to `on_progress`
+    pub async fn pull(&self, model: &str, on_progress: &mut (dyn FnMut(&PullProgress) + Send)) -> Result<(), ProviderError> {
+        let body = OllamaModelRequest { model, stream: Some(true) };
+        let res = self.http.send(self.http.post(self.url("/api/pull")).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let mut stream = res.bytes_stream();
+        let mut decoder = NdjsonDecoder::<OllamaPullChunk>::default();
+        let mut succeeded = false;
+        let mut handle = |line: OllamaPullChunk| -> Result<(), ProviderError> {
+            if let Some(err) = line.error {
+                return Err(format!("Ollama pull failed: {}", err).into());
+            }
+            succeeded = line.progress.status == "success";
+            on_progress(&line.progress);
+            Ok(())
+        };
+        while let Some(item) = stream.next().await {
+            let chunk = item.map_err(|e| e.to_string())?;
+            for line in decoder.push(&chunk)? {
+                handle(line)?;
+            }
+        }
+        if let Some(line) = decoder.finish()? {
+            handle(line)?;
+        }
+
+        if succeeded {
+            Ok(())
+        } else {
+            Err(format!("Ollama pull of {} ended before completing", model).into())
+        }
+    }
+
+    pub async fn delete_model(&self, model: &str) -> Result<(), ProviderError> {
+        let body = OllamaModelRequest { model, stream: None };
+        let res = self.http.send(self.http.delete(self.url("/api/delete")).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn show_model(&self, model: &str) -> Result<OllamaModelDetails, ProviderError> {
+        let body = OllamaModelRequest { model, stream: None };
+        let res = self.http.send_idempotent(self.http.post(self.url("/api/show")).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let body: OllamaShowResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.into_details(model))
+    }
+
+    pub async fn running_models(&self) -> Result<Vec<RunningModel>, ProviderError> {
+        let res = self.http.send(self.http.get(self.url("/api/ps")))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let body: OllamaPsResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.models.into_iter().map(RunningModel::from).collect())
+    }
+
+    /// Ask Ollama to drop `model` from memory now (a request with `keep_alive: 0`)
+    pub async fn unload_model(&self, model: &str) -> Result<(), ProviderError> {
+        let body = OllamaUnloadRequest { model, keep_alive: KeepAlive::Seconds(0) };
+        let res = self.http.send(self.http.post(self.url("/api/generate")).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+        Ok(())
+    }
+
+    pub async fn copy_model(&self, source: &str, destination: &str) -> Result<(), ProviderError> {
+        let body = OllamaCopyRequest { source, destination };
+        let res = self.http.send(self.http.post(self.url("/api/copy")).json(&body))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+        Ok(())
+    }
+}
+
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<String, ProviderError> {
+        let res = self.http.send_idempotent(self.http.post(self.url("/api/chat")).json(&self.chat_body(request, false)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let body: OllamaChatResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.message.content)
+    }
+
+    async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError> {
+        let res = self.http.send_idempotent(self.http.post(self.url("/api/chat")).json(&self.chat_body(request, true)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let mut stream = res.bytes_stream();
+        let mut decoder = NdjsonDecoder::<OllamaChatChunk>::default();
+        let mut outcome = StreamOutcome::default();
+        while let Some(item) = stream.next().await {
+            let chunk = item.map_err(|e| e.to_string())?;
+            for line in decoder.push(&chunk)? {
+                line.forward(on_chunk, &mut outcome)?;
+            }
+        }
+        if let Some(line) = decoder.finish()? {
+            line.forward(on_chunk, &mut outcome)?;
+        }
+
+        Ok(outcome)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        let res = self.http.send(self.http.get(self.url("/api/tags")))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let body: OllamaTagsResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.models.into_iter().map(|m| m.name).collect())
+    }
+
+    async fn embed(&self, model: &str, input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let res = self.http.send_idempotent(self.http.post(self.url("/api/embed")).json(&OllamaEmbedRequest { model, input }))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("Ollama", res.status()));
+        }
+
+        let body: OllamaEmbedResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.embeddings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_error_objects_fail_the_stream() {
+        let mut decoder = NdjsonDecoder::<OllamaChatChunk>::default();
+        let body = b"{\"message\":{\"role\":\"assistant\",\"content\":\"Hi\"},\"done\":false}\n{\"error\":\"model 'x' not found\"}\n";
+        let mut seen = Vec::new();
+        let mut sink = |chunk: &str| -> Result<(), String> {
+            seen.push(chunk.to_string());
+            Ok(())
+        };
+
+        let mut outcome = StreamOutcome::default();
+        let mut result = Ok(());
+        for piece in body.chunks(7) {
+            for line in decoder.push(piece).unwrap() {
+                result = result.and_then(|_| line.forward(&mut sink, &mut outcome));
+            }
+        }
+
+        assert_eq!(result.unwrap_err().to_string(), "Ollama API Error: model 'x' not found");
+        assert_eq!(seen, vec!["Hi".to_string()]);
+    }
+
+    #[test]
+    fn collects_tool_calls_and_usage() {
+        let lines = [
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\",\"tool_calls\":[{\"function\":{\"name\":\"read_file\",\"arguments\":{\"path\":\"a.txt\"}}}]},\"done\":false}",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true,\"prompt_eval_count\":26,\"eval_count\":290}",
+        ];
+        let mut sink = |_: &str| -> Result<(), String> { Ok(()) };
+        let mut outcome = StreamOutcome::default();
+        for line in lines {
+            serde_json::from_str::<OllamaChatChunk>(line).unwrap().forward(&mut sink, &mut outcome).unwrap();
+        }
+
+        assert_eq!(outcome, StreamOutcome {
+            usage: Some(Usage { prompt_tokens: 26, completion_tokens: 290 }),
+            tool_calls: vec![ToolCall {
+                id: String::new(),
+                name: "read_file".to_string(),
+                arguments: serde_json::json!({ "path": "a.txt" }),
+            }],
+        });
+    }
+
+    #[test]
+    fn show_reads_the_architecture_context_length() {
+        let body = serde_json::json!({
+            "parameters": "num_ctx 8192\nstop \"<|eot_id|>\"",
+            "template": "{{ .Prompt }}",
+            "details": { "format": "gguf", "family": "llama", "parameter_size": "8.0B", "quantization_level": "Q4_0" },
+            "model_info": { "general.architecture": "llama", "llama.context_length": 131072, "bert.context_length": 512 },
+            "capabilities": ["completion", "tools"]
+        });
+        let details = serde_json::from_value::<OllamaShowResponse>(body).unwrap().into_details("llama3.1");
+
+        assert_eq!(details.context_length, Some(131072));
+        assert_eq!(details.quantization_level, "Q4_0");
+        assert_eq!(details.capabilities, vec!["completion".to_string(), "tools".to_string()]);
+    }
+
+    #[test]
+    fn running_models_split_vram_and_ram() {
+        let body: OllamaPsResponse = serde_json::from_value(serde_json::json!({
+            "models": [{
+                "name": "llama3:8b",
+                "model": "llama3:8b",
+                "size": 6_000_000_000u64,
+                "size_vram": 4_500_000_000u64,
+                "expires_at": "2026-06-04T14:38:31.83753-07:00",
+                "details": { "parameter_size": "8.0B", "quantization_level": "Q4_0" }
+            }]
+        })).unwrap();
+        let running: Vec<RunningModel> = body.models.into_iter().map(RunningModel::from).collect();
+
+        assert_eq!(running[0].size_ram, 1_500_000_000);
+        assert_eq!(running[0].expires_at.as_deref(), Some("2026-06-04T14:38:31.83753-07:00"));
+        assert_eq!(
+            serde_json::to_value(OllamaUnloadRequest { model: "llama3:8b", keep_alive: KeepAlive::Seconds(0) }).unwrap(),
+            serde_json::json!({ "model": "llama3:8b", "keep_alive": 0 })
+        );
+    }
+
+    #[test]
+    fn pull_lines_carry_progress_or_errors() {
+        let line: OllamaPullChunk = serde_json::from_str(
+            "{\"status\":\"pulling 6a0746a1ec1a\",\"digest\":\"sha256:6a07\",\"total\":4661211808,\"completed\":1048576}",
+        ).unwrap();
+        assert_eq!(line.error, None);
+        assert_eq!(line.progress.completed, Some(1048576));
+        assert_eq!(line.progress.total, Some(4661211808));
+
+        let line: OllamaPullChunk = serde_json::from_str("{\"error\":\"pull model manifest: file does not exist\"}").unwrap();
+        assert_eq!(line.error.as_deref(), Some("pull model manifest: file does not exist"));
+    }
+
+    #[test]
+    fn options_override_presets_and_split_into_the_request() {
+        let preset = OllamaOptions {
+            num_ctx: Some(8192),
+            temperature: Some(0.25),
+            keep_alive: Some(KeepAlive::Duration("30m".to_string())),
+            ..Default::default()
+        };
+        let options = OllamaOptions {
+            temperature: Some(0.5),
+            format: Some(serde_json::json!("json")),
+            ..Default::default()
+        }.or(&preset);
+
+        let provider = OllamaProvider::new(HttpClient::new(reqwest::Client::new(), Default::default()), "http://localhost:11434/".to_string())
+            .with_options(options);
+        let request = ChatRequest { model: "llama3".to_string(), messages: Vec::new(), tools: Vec::new() };
+
+        let body = serde_json::to_value(provider.chat_body(&request, true)).unwrap();
+        assert_eq!(body, serde_json::json!({
+            "model": "llama3",
+            "messages": [],
+            "stream": true,
+            "options": { "num_ctx": 8192, "temperature": 0.5 },
+            "keep_alive": "30m",
+            "format": "json"
+        }));
+    }
+}
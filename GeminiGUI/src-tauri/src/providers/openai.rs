@@ -0,0 +1,476 @@
+use std::collections::BTreeMap;
+
+use futures_util::StreamExt;
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+
+use crate::http::HttpClient;
+use crate::redact;
+
+use super::decode::SseDecoder;
+use super::{
+    split_image, ChatMessage, ChatRequest, ChunkSink, LlmProvider, ProviderError, StreamOutcome, ToolCall,
+    ToolDeclaration, Usage,
+};
+
+/// Terminates an OpenAI SSE stream
+const DONE_EVENT: &str = "[DONE]";
+
+#[derive(Serialize)]
+struct OpenAiImageUrl {
+    url: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OpenAiContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAiImageUrl },
+}
+
+/// Plain string, or parts when the message carries images
+#[derive(Serialize)]
+#[serde(untagged)]
+enum OpenAiContent {
+    Text(String),
+    Parts(Vec<OpenAiContentPart>),
+}
+
+#[derive(Serialize)]
+struct OpenAiFunctionCall {
+    name: String,
+    /// JSON-encoded, as the protocol demands
+    arguments: String,
+}
+
+#[derive(Serialize)]
+struct OpenAiToolCall {
+    id: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: OpenAiFunctionCall,
+}
+
+#[derive(Serialize)]
+struct OpenAiMessage {
+    role: String,
+    content: OpenAiContent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<OpenAiToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+}
+
+impl From<&ChatMessage> for OpenAiMessage {
+    fn from(m: &ChatMessage) -> Self {
+        let content = match m.images.as_deref() {
+            Some(images) if !images.is_empty() => {
+                let images = images.iter().map(|image| {
+                    let (mime_type, data) = split_image(image);
+                    OpenAiContentPart::ImageUrl {
+                        image_url: OpenAiImageUrl { url: format!("data:{};base64,{}", mime_type, data) },
+                    }
+                });
+                let text = OpenAiContentPart::Text { text: m.content.clone() };
+                OpenAiContent::Parts(std::iter::once(text).chain(images).collect())
+            }
+            _ => OpenAiContent::Text(m.content.clone()),
+        };
+        Self {
+            role: m.role.clone(),
+            content,
+            tool_calls: m.tool_calls.as_ref().map(|calls| {
+                calls.iter().map(|call| OpenAiToolCall {
+                    id: call.id.clone(),
+                    kind: "function",
+                    function: OpenAiFunctionCall { name: call.name.clone(), arguments: call.arguments.to_string() },
+                }).collect()
+            }),
+            tool_call_id: m.tool_call_id.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OpenAiTool<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    function: &'a ToolDeclaration,
+}
+
+#[derive(Serialize)]
+struct OpenAiStreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Serialize)]
+struct OpenAiJsonSchema<'a> {
+    name: &'static str,
+    schema: &'a serde_json::Value,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OpenAiResponseFormat<'a> {
+    JsonSchema { json_schema: OpenAiJsonSchema<'a> },
+}
+
+#[derive(Serialize)]
+struct OpenAiChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OpenAiMessage>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAiStreamOptions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OpenAiTool<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<OpenAiResponseFormat<'a>>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponseMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiResponseMessage,
+    #[serde(default)]
+    index: u32,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChatResponse {
+    #[serde(default)]
+    choices: Vec<OpenAiChoice>,
+}
+
+/// Fragment of a streamed tool call; `index` ties the fragments together
+#[derive(Deserialize)]
+struct OpenAiToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    function: Option<OpenAiFunctionDelta>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiFunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiDelta {
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCallDelta>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChunkChoice {
+    delta: Option<OpenAiDelta>,
+    /// Position among the `n` alternatives; only the first is streamed
+    #[serde(default)]
+    index: u32,
+}
+
+/// One event of a streaming `/chat/completions` response
+#[derive(Deserialize)]
+struct OpenAiChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiChunkChoice>,
+    usage: Option<OpenAiUsage>,
+    error: Option<OpenAiError>,
+}
+
+/// Tool calls being assembled from stream fragments: name and argument text per index
+#[derive(Default)]
+struct PendingToolCalls(BTreeMap<usize, (String, String)>);
+
+impl PendingToolCalls {
+    fn push(&mut self, delta: OpenAiToolCallDelta) {
+        let (name, arguments) = self.0.entry(delta.index).or_default();
+        if let Some(function) = delta.function {
+            name.push_str(function.name.as_deref().unwrap_or_default());
+            arguments.push_str(function.arguments.as_deref().unwrap_or_default());
+        }
+    }
+
+    fn finish(self) -> Result<Vec<ToolCall>, ProviderError> {
+        self.0.into_values().map(|(name, arguments)| {
+            let arguments = if arguments.trim().is_empty() {
+                serde_json::Value::Object(Default::default())
+            } else {
+                serde_json::from_str(&arguments)
+                    .map_err(|e| format!("Invalid arguments for tool call {}: {}", name, e))?
+            };
+            Ok(ToolCall { id: String::new(), name, arguments })
+        }).collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenAiModel {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiModelsResponse {
+    data: Vec<OpenAiModel>,
+}
+
+#[derive(Serialize)]
+struct OpenAiEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+/// Any server speaking the OpenAI `/v1` protocol (llama.cpp server, vLLM, LM Studio, ...)
+pub(crate) struct OpenAiProvider {
+    http: HttpClient,
+    base_url: String,
+    api_key: Option<String>,
+    json_schema: Option<serde_json::Value>,
+}
+
+impl OpenAiProvider {
+    /// `base_url` may be given with or without the trailing `/v1`
+    pub fn new(http: HttpClient, base_url: String, api_key: Option<String>) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = if base_url.ends_with("/v1") { base_url.to_string() } else { format!("{}/v1", base_url) };
+        if let Some(key) = &api_key {
+            redact::register_secret(key);
+        }
+        Self { http, base_url, api_key, json_schema: None }
+    }
+
+    /// Constrain answers to `schema` via `response_format`
+    pub fn with_json_schema(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.json_schema = schema;
+        self
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.http.get(format!("{}{}", self.base_url, path)))
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        self.authorize(self.http.post(format!("{}{}", self.base_url, path)))
+    }
+
+    fn chat_body<'a>(&'a self, request: &'a ChatRequest, stream: bool) -> OpenAiChatRequest<'a> {
+        OpenAiChatRequest {
+            model: &request.model,
+            messages: request.messages.iter().map(OpenAiMessage::from).collect(),
+            stream,
+            stream_options: stream.then_some(OpenAiStreamOptions { include_usage: true }),
+            tools: request.tools.iter().map(|tool| OpenAiTool { kind: "function", function: tool }).collect(),
+            response_format: self.json_schema.as_ref().map(|schema| OpenAiResponseFormat::JsonSchema {
+                json_schema: OpenAiJsonSchema { name: "response", schema },
+            }),
+        }
+    }
+
+    /// Forward one SSE event's text, collecting tool call fragments and usage
+    fn emit_event(
+        event: &str,
+        on_chunk: &mut ChunkSink<'_>,
+        pending: &mut PendingToolCalls,
+        outcome: &mut StreamOutcome,
+    ) -> Result<(), ProviderError> {
+        if event.trim() == DONE_EVENT {
+            return Ok(());
+        }
+        let chunk: OpenAiChunk = serde_json::from_str(event)
+            .map_err(|e| format!("Invalid OpenAI response: {}", e))?;
+        if let Some(err) = chunk.error {
+            return Err(format!("OpenAI API Error: {}", err.message).into());
+        }
+        for delta in chunk.choices.into_iter().filter(|c| c.index == 0).filter_map(|c| c.delta) {
+            if let Some(text) = delta.content.filter(|t| !t.is_empty()) {
+                on_chunk(&text)?;
+            }
+            for call in delta.tool_calls {
+                pending.push(call);
+            }
+        }
+        if let Some(usage) = chunk.usage {
+            outcome.usage = Some(Usage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn chat(&self, request: &ChatRequest) -> Result<String, ProviderError> {
+        let res = self.http.send_idempotent(self.post("/chat/completions").json(&self.chat_body(request, false)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("OpenAI", res.status()));
+        }
+
+        let body: OpenAiChatResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.choices.into_iter().find(|c| c.index == 0).and_then(|c| c.message.content).unwrap_or_default())
+    }
+
+    async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError> {
+        let res = self.http.send_idempotent(self.post("/chat/completions").json(&self.chat_body(request, true)))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("OpenAI", res.status()));
+        }
+
+        let mut stream = res.bytes_stream();
+        let mut decoder = SseDecoder::default();
+        let mut pending = PendingToolCalls::default();
+        let mut outcome = StreamOutcome::default();
+        while let Some(item) = stream.next().await {
+            let chunk = item.map_err(|e| e.to_string())?;
+            for event in decoder.push(&chunk)? {
+                Self::emit_event(&event, on_chunk, &mut pending, &mut outcome)?;
+            }
+        }
+        for event in decoder.finish()? {
+            Self::emit_event(&event, on_chunk, &mut pending, &mut outcome)?;
+        }
+
+        outcome.tool_calls = pending.finish()?;
+        Ok(outcome)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+        let res = self.http.send(self.get("/models"))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("OpenAI", res.status()));
+        }
+
+        let body: OpenAiModelsResponse = res.json().await.map_err(|e| e.to_string())?;
+        Ok(body.data.into_iter().map(|m| m.id).collect())
+    }
+
+    async fn embed(&self, model: &str, input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let res = self.http.send_idempotent(self.post("/embeddings").json(&OpenAiEmbedRequest { model, input }))
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ProviderError::status("OpenAI", res.status()));
+        }
+
+        let mut body: OpenAiEmbedResponse = res.json().await.map_err(|e| e.to_string())?;
+        body.data.sort_by_key(|e| e.index);
+        Ok(body.data.into_iter().map(|e| e.embedding).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assembles_text_tool_calls_and_usage_from_events() {
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"Checking\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"c1\",\"function\":{\"name\":\"read_file\",\"arguments\":\"{\\\"pa\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"th\\\":\\\"a.txt\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":7}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut seen = String::new();
+        let mut sink = |chunk: &str| -> Result<(), String> {
+            seen.push_str(chunk);
+            Ok(())
+        };
+
+        let mut decoder = SseDecoder::default();
+        let mut pending = PendingToolCalls::default();
+        let mut outcome = StreamOutcome::default();
+        for piece in body.as_bytes().chunks(11) {
+            for event in decoder.push(piece).unwrap() {
+                OpenAiProvider::emit_event(&event, &mut sink, &mut pending, &mut outcome).unwrap();
+            }
+        }
+        outcome.tool_calls = pending.finish().unwrap();
+
+        assert_eq!(seen, "Checking");
+        assert_eq!(outcome, StreamOutcome {
+            usage: Some(Usage { prompt_tokens: 12, completion_tokens: 7 }),
+            tool_calls: vec![ToolCall {
+                id: String::new(),
+                name: "read_file".to_string(),
+                arguments: serde_json::json!({ "path": "a.txt" }),
+            }],
+        });
+    }
+
+    #[test]
+    fn only_the_first_choice_is_streamed() {
+        let events = [
+            r#"{"choices":[{"index":0,"delta":{"content":"Hel"}},{"index":1,"delta":{"content":"Bon"}}]}"#,
+            r#"{"choices":[{"index":1,"delta":{"content":"jour","tool_calls":[{"index":0,"id":"c2","function":{"name":"rm","arguments":"{}"}}]}},{"index":0,"delta":{"content":"lo"}}]}"#,
+        ];
+        let mut seen = String::new();
+        let mut sink = |chunk: &str| -> Result<(), String> {
+            seen.push_str(chunk);
+            Ok(())
+        };
+        let mut pending = PendingToolCalls::default();
+        let mut outcome = StreamOutcome::default();
+        for event in events {
+            OpenAiProvider::emit_event(event, &mut sink, &mut pending, &mut outcome).unwrap();
+        }
+        assert_eq!(seen, "Hello");
+        assert!(pending.finish().unwrap().is_empty());
+    }
+
+    #[test]
+    fn base_url_gets_the_version_prefix_once() {
+        let http = HttpClient::new(reqwest::Client::new(), Default::default());
+        assert_eq!(OpenAiProvider::new(http.clone(), "http://localhost:8080/".to_string(), None).base_url, "http://localhost:8080/v1");
+        assert_eq!(OpenAiProvider::new(http, "http://localhost:1234/v1".to_string(), None).base_url, "http://localhost:1234/v1");
+    }
+}
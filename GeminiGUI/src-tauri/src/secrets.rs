@@ -0,0 +1,307 @@
+// ============================================================================
+// SECURITY: Secret store
+// ============================================================================
+//
+// API keys are kept in `secrets.json` under the app data dir, encrypted with
+// ChaCha20-Poly1305. The encryption key is derived with Argon2id from
+// machine-bound material (machine ID + user name) and, when set, the
+// GEMINI_SECRETS_PASSPHRASE environment variable, so a copied file is useless
+// elsewhere. Secret values never leave the backend: the frontend can only
+// set, check and delete them, and provider commands look keys up here.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::redact;
+
+pub(crate) const GEMINI_API_KEY: &str = "GEMINI_API_KEY";
+
+const STORE_VERSION: u32 = 1;
+
+/// On-disk format; all binary fields are base64
+#[derive(Serialize, Deserialize)]
+struct SealedStore {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub(crate) struct SecretStore {
+    path: PathBuf,
+    /// Serialises read-modify-write cycles and caches the key for the file's salt
+    key_cache: Mutex<Option<([u8; 16], [u8; 32])>>,
+}
+
+impl SecretStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            path: dir.join("secrets.json"),
+            key_cache: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>, String> {
+        let mut cache = self.key_cache.lock().unwrap();
+        let (secrets, _) = self.load(&mut cache)?;
+        Ok(secrets.get(name).cloned())
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut HashMap<String, String>) -> bool) -> Result<bool, String> {
+        let mut cache = self.key_cache.lock().unwrap();
+        let (mut secrets, salt) = self.load(&mut cache)?;
+        let changed = apply(&mut secrets);
+        if changed {
+            self.save(&mut cache, &secrets, salt)?;
+        }
+        Ok(changed)
+    }
+
+    /// Gemini API key: the store first, then the legacy `.env` file / process environment
+    pub fn gemini_api_key(&self) -> Result<String, String> {
+        if let Some(key) = self.get(GEMINI_API_KEY)? {
+            return Ok(key);
+        }
+        let env_file = crate::read_env_file().unwrap_or_default();
+        let key = ["GEMINI_API_KEY", "GOOGLE_API_KEY"]
+            .iter()
+            .find_map(|name| env_file.get(*name).cloned().or_else(|| std::env::var(name).ok()))
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| "Gemini API key is not set".to_string())?;
+        redact::register_secret(&key);
+        Ok(key)
+    }
+
+    fn load(&self, cache: &mut Option<([u8; 16], [u8; 32])>) -> Result<(HashMap<String, String>, [u8; 16]), String> {
+        if !self.path.exists() {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+            return Ok((HashMap::new(), salt));
+        }
+
+        let content = fs::read_to_string(&self.path).map_err(|e| format!("Failed to read secret store: {}", e))?;
+        let sealed: SealedStore = serde_json::from_str(&content).map_err(|e| format!("Corrupt secret store: {}", e))?;
+        if sealed.version != STORE_VERSION {
+            return Err(format!("Unsupported secret store version {}", sealed.version));
+        }
+
+        let salt: [u8; 16] = decode_fixed(&sealed.salt)?;
+        let nonce: [u8; 12] = decode_fixed(&sealed.nonce)?;
+        let ciphertext = BASE64.decode(&sealed.ciphertext).map_err(|e| format!("Corrupt secret store: {}", e))?;
+
+        let cipher = cipher_for(cache, salt)?;
+        let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Secret store cannot be decrypted on this machine (or with this passphrase)".to_string())?;
+        let secrets: HashMap<String, String> = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Corrupt secret store: {}", e))?;
+
+        for value in secrets.values() {
+            redact::register_secret(value);
+        }
+        Ok((secrets, salt))
+    }
+
+    fn save(&self, cache: &mut Option<([u8; 16], [u8; 32])>, secrets: &HashMap<String, String>, salt: [u8; 16]) -> Result<(), String> {
+        let cipher = cipher_for(cache, salt)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+        let ciphertext = cipher.encrypt(&nonce, plaintext.as_ref()).map_err(|_| "Failed to encrypt secrets".to_string())?;
+
+        let sealed = SealedStore {
+            version: STORE_VERSION,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&sealed).map_err(|e| e.to_string())?;
+        // Replace the file in one step so a crash never leaves it half written
+        let temp = self.path.with_extension("json.tmp");
+        write_private(&temp, content.as_bytes())
+            .and_then(|_| fs::rename(&temp, &self.path))
+            .map_err(|e| format!("Failed to write secret store: {}", e))
+    }
+}
+
+/// Write `content` to a fresh `path` readable by the current user only (0600 on Unix)
+fn write_private(path: &Path, content: &[u8]) -> io::Result<()> {
+    // The mode only applies to newly created files
+    if let Err(e) = fs::remove_file(path) {
+        if e.kind() != io::ErrorKind::NotFound {
+            return Err(e);
+        }
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+fn decode_fixed<const N: usize>(value: &str) -> Result<[u8; N], String> {
+    BASE64.decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "Corrupt secret store".to_string())
+}
+
+/// Cipher for `salt`, deriving (and caching) the key only when the salt changes
+fn cipher_for(cache: &mut Option<([u8; 16], [u8; 32])>, salt: [u8; 16]) -> Result<ChaCha20Poly1305, String> {
+    let key = match cache {
+        Some((cached_salt, key)) if *cached_salt == salt => *key,
+        _ => {
+            let mut key = [0u8; 32];
+            Argon2::default()
+                .hash_password_into(key_material().as_bytes(), &salt, &mut key)
+                .map_err(|e| format!("Failed to derive secret store key: {}", e))?;
+            *cache = Some((salt, key));
+            key
+        }
+    };
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn key_material() -> String {
+    let user = std::env::var("USERNAME").or_else(|_| std::env::var("USER")).unwrap_or_default();
+    let passphrase = std::env::var("GEMINI_SECRETS_PASSPHRASE").unwrap_or_default();
+    format!("geminigui-secrets\n{}\n{}\n{}", machine_id().unwrap_or_default(), user, passphrase)
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKLM\SOFTWARE\Microsoft\Cryptography", "/v", "MachineGuid"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("MachineGuid"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(|id| id.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("IOPlatformUUID"))
+        .and_then(|line| line.split('"').nth(3))
+        .map(|id| id.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn machine_id() -> Option<String> {
+    None
+}
+
+/// Names that look like credentials; such `.env` entries are never sent to the frontend
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let name = name.to_uppercase();
+    ["KEY", "TOKEN", "SECRET", "PASSWORD"].iter().any(|marker| name.contains(marker))
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 128 {
+        return Err("Secret name must be 1-128 characters".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn set_secret(secrets: State<'_, SecretStore>, name: String, value: String) -> Result<(), String> {
+    validate_name(&name)?;
+    if value.is_empty() {
+        return Err("Secret value cannot be empty".to_string());
+    }
+    redact::register_secret(&value);
+    secrets.update(|map| {
+        map.insert(name, value);
+        true
+    })?;
+    Ok(())
+}
+
+/// Whether `name` is set. The Gemini key also counts when it only comes from
+/// .env or the environment, since `gemini_api_key` falls back to those.
+#[tauri::command]
+pub(crate) fn has_secret(secrets: State<'_, SecretStore>, name: String) -> Result<bool, String> {
+    if name == GEMINI_API_KEY {
+        return Ok(secrets.gemini_api_key().is_ok());
+    }
+    Ok(secrets.get(&name)?.is_some())
+}
+
+#[tauri::command]
+pub(crate) fn delete_secret(secrets: State<'_, SecretStore>, name: String) -> Result<bool, String> {
+    secrets.update(|map| map.remove(&name).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_sealed_file() {
+        let dir = std::env::temp_dir().join(format!("geminigui-secrets-{}", std::process::id()));
+        let store = SecretStore::new(dir.clone());
+        store.update(|map| map.insert("OPENAI_API_KEY".to_string(), "sk-test-1234567890".to_string()).is_none()).unwrap();
+
+        let content = fs::read_to_string(dir.join("secrets.json")).unwrap();
+        assert!(!content.contains("sk-test-1234567890"));
+
+        let reopened = SecretStore::new(dir.clone());
+        assert_eq!(reopened.get("OPENAI_API_KEY").unwrap().as_deref(), Some("sk-test-1234567890"));
+        assert!(reopened.update(|map| map.remove("OPENAI_API_KEY").is_some()).unwrap());
+        assert_eq!(reopened.get("OPENAI_API_KEY").unwrap(), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn store_is_replaced_atomically_and_private() {
+        let dir = std::env::temp_dir().join(format!("geminigui-secrets-private-{}", std::process::id()));
+        let store = SecretStore::new(dir.clone());
+        store.update(|map| map.insert("A".to_string(), "first-secret".to_string()).is_none()).unwrap();
+        store.update(|map| map.insert("A".to_string(), "second-secret".to_string()).is_some()).unwrap();
+
+        assert!(!dir.join("secrets.json.tmp").exists());
+        assert_eq!(SecretStore::new(dir.clone()).get("A").unwrap().as_deref(), Some("second-secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join("secrets.json")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let _ = fs::remove_dir_all(dir);
+    }
+}
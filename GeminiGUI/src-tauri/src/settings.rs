@@ -0,0 +1,79 @@
+// ============================================================================
+// BACKEND SETTINGS
+// ============================================================================
+//
+// Settings that only the Rust side acts on (network, retry policy, ...). Persisted as
+// JSON next to the other GeminiCLI state files; missing fields fall back to
+// their defaults so older files keep loading.
+
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::http::{HttpClient, HttpState, NetworkSettings, RetryPolicy};
+use crate::models::ModelRanking;
+use crate::providers::OllamaOptions;
+use crate::usage::ModelPrice;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub(crate) struct BackendSettings {
+    pub network: NetworkSettings,
+    pub retry: RetryPolicy,
+    /// Default Ollama options per model name, e.g. `"llama3:8b": { "num_ctx": 8192 }`
+    pub ollama_presets: HashMap<String, OllamaOptions>,
+    /// Price table for usage reports, keyed by model name or name prefix
+    pub prices: HashMap<String, ModelPrice>,
+    /// Order of model pickers: pinned models, tiers and demoted name parts
+    pub model_ranking: ModelRanking,
+    /// Address the supervised Ollama server listens on, e.g. `http://127.0.0.1:11500`;
+    /// `OLLAMA_HOST` (environment, then `.env`) is used when unset
+    pub ollama_endpoint: Option<String>,
+}
+
+fn get_settings_path() -> std::path::PathBuf {
+    crate::get_base_dir().join("backend_settings.json")
+}
+
+pub(crate) fn read_settings() -> BackendSettings {
+    let path = get_settings_path();
+    if !path.exists() {
+        return BackendSettings::default();
+    }
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => BackendSettings::default(),
+    }
+}
+
+pub(crate) fn write_settings(settings: &BackendSettings) -> Result<(), String> {
+    let path = get_settings_path();
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// `options` for `model`, with unset fields filled from the model's preset
+pub(crate) fn ollama_options(model: &str, options: Option<OllamaOptions>) -> OllamaOptions {
+    let options = options.unwrap_or_default();
+    match read_settings().ollama_presets.get(model) {
+        Some(preset) => options.or(preset),
+        None => options,
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_backend_settings() -> Result<BackendSettings, String> {
+    Ok(read_settings())
+}
+
+/// Persist `settings` and swap in an HTTP client built from them. Invalid
+/// network settings (bad proxy URL, unreadable CA file) are rejected unsaved.
+#[tauri::command]
+pub(crate) fn save_backend_settings(http: State<'_, HttpState>, settings: BackendSettings) -> Result<BackendSettings, String> {
+    let client = HttpClient::from_settings(&settings.network, &settings.retry)?;
+    write_settings(&settings)?;
+    http.replace(client);
+    Ok(settings)
+}
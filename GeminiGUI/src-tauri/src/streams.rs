@@ -0,0 +1,663 @@
+// ============================================================================
+// CHAT STREAMS
+// ============================================================================
+//
+// Each streaming prompt runs as a background task identified by a request ID.
+// Every payload emitted on `ollama-event` carries that ID and a per-stream
+// sequence number, so several chats can stream at the same time. Running
+// streams are tracked in `StreamRegistry` so they can be cancelled.
+//
+// A stream is given an ordered chain of provider+model targets. A target that
+// fails with 429/5xx, cannot be reached or answers with nothing before
+// emitting any text is skipped in favour of the next one, mirroring the
+// Dijkstra/Mandated chains in AgentSwarm.psm1. `stream-model` reports which target answered, and
+// `stream-usage` its token counts once it finishes.
+//
+// When the model asks for tools, each call is emitted on `tool-call` and the
+// stream waits until the frontend runs it and answers via `submit_tool_result`,
+// then continues the conversation with the results (replacing the `EXEC:`
+// prefix convention of AgentSwarm.psm1).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::{AbortHandle, Abortable};
+use serde::Serialize;
+use tauri::{Emitter, State, Window};
+use tokio::sync::oneshot;
+
+use crate::providers::{ChatMessage, ChatRequest, LlmProvider, Provider, ProviderError, ToolCall, ToolDeclaration, Usage};
+use crate::redact::redact;
+use crate::usage::record_usage;
+
+const STREAM_EVENT: &str = "ollama-event";
+const MODEL_EVENT: &str = "stream-model";
+const TOOL_EVENT: &str = "tool-call";
+
+/// Tool round trips allowed per stream before it is ended as runaway
+const MAX_TOOL_ROUNDS: usize = 8;
+
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Serialize)]
+struct ChatStreamPayload {
+    request_id: String,
+    seq: u64,
+    chunk: String,
+    done: bool,
+    cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct SkippedModel {
+    provider: String,
+    model: String,
+    error: String,
+}
+
+#[derive(Clone, Serialize)]
+struct ModelSelectedPayload {
+    request_id: String,
+    provider: String,
+    model: String,
+    attempt: usize,
+    skipped: Vec<SkippedModel>,
+}
+
+#[derive(Clone, Serialize)]
+struct ToolCallPayload {
+    request_id: String,
+    call_id: String,
+    name: String,
+    arguments: serde_json::Value,
+}
+
+/// A provider plus the model to ask it for; one link of a fallback chain
+pub(crate) struct StreamTarget<P = Provider> {
+    pub provider: P,
+    pub model: String,
+}
+
+/// Where a stream's events end up: the invoking window in the app, a
+/// recorder in tests
+pub(crate) trait StreamSink {
+    fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) -> Result<(), String>;
+
+    /// Account for a finished completion. Never fails: accounting must not
+    /// turn a delivered answer into a failure.
+    fn record_usage(&self, request_id: &str, provider: &str, model: &str, usage: Usage);
+}
+
+impl StreamSink for Window {
+    fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) -> Result<(), String> {
+        Emitter::emit(self, event, payload).map_err(|e| e.to_string())
+    }
+
+    fn record_usage(&self, request_id: &str, provider: &str, model: &str, usage: Usage) {
+        let _ = record_usage(self, request_id, provider, model, usage);
+    }
+}
+
+/// Abort handles of every running stream, keyed by request ID, and the tool
+/// calls they are waiting on, keyed by call ID (Tauri managed state)
+#[derive(Default, Clone)]
+pub(crate) struct StreamRegistry {
+    active: Arc<Mutex<HashMap<String, AbortHandle>>>,
+    pending_tools: Arc<Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+}
+
+impl StreamRegistry {
+    /// Register a new stream; fails if `request_id` is already in flight
+    fn insert(&self, request_id: &str, handle: AbortHandle) -> Result<(), String> {
+        let mut active = self.active.lock().unwrap();
+        if active.contains_key(request_id) {
+            return Err(format!("Stream {} is already running", request_id));
+        }
+        active.insert(request_id.to_string(), handle);
+        Ok(())
+    }
+
+    /// Forget a finished stream, including tool calls it was still waiting on
+    fn remove(&self, request_id: &str) {
+        self.active.lock().unwrap().remove(request_id);
+        let prefix = format!("{}_call_", request_id);
+        self.pending_tools.lock().unwrap().retain(|call_id, _| !call_id.starts_with(&prefix));
+    }
+
+    fn wait_for_tool(&self, call_id: &str) -> oneshot::Receiver<serde_json::Value> {
+        let (tx, rx) = oneshot::channel();
+        self.pending_tools.lock().unwrap().insert(call_id.to_string(), tx);
+        rx
+    }
+
+    /// Hand a tool result to the stream waiting for it; false if none is
+    fn resolve_tool(&self, call_id: &str, result: serde_json::Value) -> bool {
+        match self.pending_tools.lock().unwrap().remove(call_id) {
+            Some(tx) => tx.send(result).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Abort a running stream; returns false if it already finished
+    fn cancel(&self, request_id: &str) -> bool {
+        match self.active.lock().unwrap().remove(request_id) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Emits the payloads of a single stream, numbering them in order
+struct StreamEmitter<S> {
+    sink: S,
+    request_id: String,
+    seq: u64,
+    tool_calls: u64,
+}
+
+impl<S: StreamSink> StreamEmitter<S> {
+    fn emit(&mut self, chunk: &str, done: bool, error: Option<String>) -> Result<(), String> {
+        self.send(ChatStreamPayload {
+            request_id: self.request_id.clone(),
+            seq: self.seq,
+            chunk: chunk.to_string(),
+            done,
+            cancelled: false,
+            error,
+        })
+    }
+
+    fn emit_cancelled(&mut self) -> Result<(), String> {
+        self.send(ChatStreamPayload {
+            request_id: self.request_id.clone(),
+            seq: self.seq,
+            chunk: String::new(),
+            done: true,
+            cancelled: true,
+            error: None,
+        })
+    }
+
+    fn send(&mut self, payload: ChatStreamPayload) -> Result<(), String> {
+        self.seq += 1;
+        self.sink.emit(STREAM_EVENT, payload)
+    }
+
+    fn emit_model<P: LlmProvider>(&self, target: &StreamTarget<P>, attempt: usize, skipped: &[SkippedModel]) -> Result<(), String> {
+        self.sink.emit(MODEL_EVENT, ModelSelectedPayload {
+            request_id: self.request_id.clone(),
+            provider: target.provider.name().to_string(),
+            model: target.model.clone(),
+            attempt,
+            skipped: skipped.to_vec(),
+        })
+    }
+
+    /// Stream-unique tool call ID; `StreamRegistry::remove` relies on the prefix
+    fn next_tool_call_id(&mut self) -> String {
+        self.tool_calls += 1;
+        format!("{}_call_{}", self.request_id, self.tool_calls)
+    }
+
+    fn emit_tool_call(&self, call: &ToolCall) -> Result<(), String> {
+        self.sink.emit(TOOL_EVENT, ToolCallPayload {
+            request_id: self.request_id.clone(),
+            call_id: call.id.clone(),
+            name: call.name.clone(),
+            arguments: call.arguments.clone(),
+        })
+    }
+}
+
+/// Emit `calls`, wait for every result and return the turns to append: the
+/// assistant turn that made the calls, then one `tool` turn per result
+async fn run_tool_calls<S: StreamSink>(
+    emitter: &mut StreamEmitter<S>,
+    registry: &StreamRegistry,
+    text: String,
+    mut calls: Vec<ToolCall>,
+) -> Result<Vec<ChatMessage>, String> {
+    let mut waiting = Vec::new();
+    for call in &mut calls {
+        call.id = emitter.next_tool_call_id();
+        // Register before emitting so a fast answer cannot miss us
+        waiting.push(registry.wait_for_tool(&call.id));
+        emitter.emit_tool_call(call)?;
+    }
+
+    let mut turns = Vec::with_capacity(calls.len() + 1);
+    for (call, rx) in calls.iter().zip(waiting) {
+        let result = rx.await.map_err(|_| format!("Tool call {} was abandoned", call.id))?;
+        let mut turn = ChatMessage::new("tool", match result {
+            serde_json::Value::String(text) => text,
+            other => other.to_string(),
+        });
+        turn.tool_name = Some(call.name.clone());
+        turn.tool_call_id = Some(call.id.clone());
+        turns.push(turn);
+    }
+
+    let mut assistant = ChatMessage::new("assistant", text);
+    assistant.tool_calls = Some(calls);
+    turns.insert(0, assistant);
+    Ok(turns)
+}
+
+/// Try each target in order until one answers. Once a target has emitted
+/// text or tool calls it is committed to: later errors end the stream instead
+/// of falling through, so the frontend never sees two answers spliced together.
+async fn stream_with_fallback<S, P>(
+    emitter: &mut StreamEmitter<S>,
+    registry: &StreamRegistry,
+    targets: &[StreamTarget<P>],
+    messages: Vec<ChatMessage>,
+    tools: Vec<ToolDeclaration>,
+) -> Result<(), String>
+where
+    S: StreamSink + Send,
+    P: LlmProvider + Sync,
+{
+    let mut skipped: Vec<SkippedModel> = Vec::new();
+
+    for (attempt, target) in targets.iter().enumerate() {
+        let mut request = ChatRequest {
+            model: target.model.clone(),
+            messages: messages.clone(),
+            tools: tools.clone(),
+        };
+        let mut answered = false;
+        let mut rounds = 0;
+
+        let error = loop {
+            let mut text = String::new();
+            let mut on_chunk = |chunk: &str| {
+                if !answered {
+                    answered = true;
+                    emitter.emit_model(target, attempt, &skipped)?;
+                }
+                text.push_str(chunk);
+                emitter.emit(chunk, false, None)
+            };
+            let outcome = match target.provider.stream(&request, &mut on_chunk).await {
+                Ok(outcome) => outcome,
+                Err(e) if !answered && e.is_transient() => break e,
+                Err(e) => return Err(e.into()),
+            };
+
+            if let Some(usage) = outcome.usage {
+                emitter.sink.record_usage(&emitter.request_id, target.provider.name(), &target.model, usage);
+            }
+            if outcome.tool_calls.is_empty() {
+                if answered {
+                    return Ok(());
+                }
+                break ProviderError::Other("Empty response".to_string());
+            }
+
+            if !answered {
+                answered = true;
+                emitter.emit_model(target, attempt, &skipped)?;
+            }
+            rounds += 1;
+            if rounds > MAX_TOOL_ROUNDS {
+                return Err(format!("Stopped after {} rounds of tool calls", MAX_TOOL_ROUNDS));
+            }
+            let turns = run_tool_calls(emitter, registry, text, outcome.tool_calls).await?;
+            request.messages.extend(turns);
+        };
+
+        skipped.push(SkippedModel {
+            provider: target.provider.name().to_string(),
+            model: target.model.clone(),
+            error: String::from(error),
+        });
+    }
+
+    let summary = skipped.iter()
+        .map(|s| format!("{}/{}: {}", s.provider, s.model, s.error))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Err(format!("All models failed ({})", summary))
+}
+
+fn new_request_id() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    format!("stream_{}_{}", millis, NEXT_STREAM.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Start streaming `messages` through the `targets` chain in the background
+/// and return the request ID. `tools` may be called along the way. The final
+/// payload has `done: true` and either an `error` if every target failed or
+/// `cancelled: true` if it was stopped through `cancel_stream`.
+///
+/// Callers should pass their own `request_id`: payloads can be emitted before
+/// the command returns, and only a caller-chosen ID can be filtered on from
+/// the first chunk. One is generated when it is omitted.
+pub(crate) fn spawn_stream(
+    registry: &StreamRegistry,
+    window: Window,
+    request_id: Option<String>,
+    targets: Vec<StreamTarget>,
+    messages: Vec<ChatMessage>,
+    tools: Vec<ToolDeclaration>,
+) -> Result<String, String> {
+    let request_id = request_id.unwrap_or_else(new_request_id);
+    let task = start_stream(registry, window, request_id.clone(), targets, messages, tools)?;
+    tauri::async_runtime::spawn(task);
+    Ok(request_id)
+}
+
+/// Register the stream under `request_id` and return the task that runs it
+fn start_stream<S, P>(
+    registry: &StreamRegistry,
+    sink: S,
+    request_id: String,
+    targets: Vec<StreamTarget<P>>,
+    messages: Vec<ChatMessage>,
+    tools: Vec<ToolDeclaration>,
+) -> Result<impl Future<Output = ()>, String>
+where
+    S: StreamSink + Send,
+    P: LlmProvider + Sync,
+{
+    let (handle, abort) = AbortHandle::new_pair();
+    registry.insert(&request_id, handle)?;
+    let registry = registry.clone();
+    let mut emitter = StreamEmitter {
+        sink,
+        request_id,
+        seq: 0,
+        tool_calls: 0,
+    };
+
+    Ok(async move {
+        // Aborting drops the provider future, and with it the reqwest body and connection
+        let work = stream_with_fallback(&mut emitter, &registry, &targets, messages, tools);
+        let result = Abortable::new(work, abort).await;
+        registry.remove(&emitter.request_id);
+
+        let _ = match result {
+            Ok(result) => emitter.emit("", true, result.err().map(|e| redact(&e))),
+            Err(_aborted) => emitter.emit_cancelled(),
+        };
+    })
+}
+
+/// Stop a running stream. Returns false if no stream with that ID is running.
+#[tauri::command]
+pub(crate) fn cancel_stream(registry: State<'_, StreamRegistry>, id: String) -> bool {
+    registry.cancel(&id)
+}
+
+/// Answer a `tool-call` event. `result` is handed to the model as the tool's
+/// output. Returns false if no stream is waiting for that call.
+#[tauri::command]
+pub(crate) fn submit_tool_result(registry: State<'_, StreamRegistry>, call_id: String, result: serde_json::Value) -> bool {
+    registry.resolve_tool(&call_id, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    use crate::providers::{ChunkSink, StreamOutcome};
+
+    /// What the fake provider does on one `stream` call
+    enum Step {
+        Reply(Vec<&'static str>, Vec<ToolCall>),
+        /// Emit the chunks, then fail
+        Fail(Vec<&'static str>, ProviderError),
+        Hang,
+    }
+
+    struct FakeProvider {
+        steps: Mutex<VecDeque<Step>>,
+        requests: Arc<Mutex<Vec<ChatRequest>>>,
+    }
+
+    fn target(model: &str, steps: Vec<Step>) -> StreamTarget<FakeProvider> {
+        StreamTarget {
+            provider: FakeProvider {
+                steps: Mutex::new(steps.into()),
+                requests: Arc::default(),
+            },
+            model: model.to_string(),
+        }
+    }
+
+    impl LlmProvider for FakeProvider {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        async fn chat(&self, _request: &ChatRequest) -> Result<String, ProviderError> {
+            Err(ProviderError::Other("chat is not used by stream tests".to_string()))
+        }
+
+        async fn stream(&self, request: &ChatRequest, on_chunk: &mut ChunkSink<'_>) -> Result<StreamOutcome, ProviderError> {
+            self.requests.lock().unwrap().push(request.clone());
+            let step = self.steps.lock().unwrap().pop_front().expect("unexpected stream call");
+            match step {
+                Step::Reply(chunks, tool_calls) => {
+                    for chunk in chunks {
+                        on_chunk(chunk)?;
+                    }
+                    let usage = Some(Usage { prompt_tokens: 3, completion_tokens: 5 });
+                    Ok(StreamOutcome { usage, tool_calls })
+                }
+                Step::Fail(chunks, err) => {
+                    for chunk in chunks {
+                        on_chunk(chunk)?;
+                    }
+                    Err(err)
+                }
+                Step::Hang => std::future::pending().await,
+            }
+        }
+
+        async fn list_models(&self) -> Result<Vec<String>, ProviderError> {
+            Err(ProviderError::Other("list_models is not used by stream tests".to_string()))
+        }
+
+        async fn embed(&self, _model: &str, _input: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+            Err(ProviderError::Other("embed is not used by stream tests".to_string()))
+        }
+    }
+
+    /// Records every event and usage report
+    #[derive(Clone, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+        usage: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn payloads(&self, event: &str) -> Vec<serde_json::Value> {
+            self.events.lock().unwrap().iter()
+                .filter(|(name, _)| name == event)
+                .map(|(_, payload)| payload.clone())
+                .collect()
+        }
+
+        fn chunks(&self) -> Vec<String> {
+            self.payloads(STREAM_EVENT).iter()
+                .filter(|p| p["done"] == false)
+                .map(|p| p["chunk"].as_str().unwrap().to_string())
+                .collect()
+        }
+
+        fn last(&self) -> serde_json::Value {
+            self.payloads(STREAM_EVENT).pop().expect("no stream payload")
+        }
+    }
+
+    impl StreamSink for Recorder {
+        fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) -> Result<(), String> {
+            let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+            self.events.lock().unwrap().push((event.to_string(), payload));
+            Ok(())
+        }
+
+        fn record_usage(&self, _request_id: &str, _provider: &str, model: &str, _usage: Usage) {
+            self.usage.lock().unwrap().push(model.to_string());
+        }
+    }
+
+    fn status(code: u16) -> ProviderError {
+        ProviderError::Status { status: code, message: format!("HTTP {}", code) }
+    }
+
+    async fn run(targets: Vec<StreamTarget<FakeProvider>>) -> Recorder {
+        let recorder = Recorder::default();
+        let registry = StreamRegistry::default();
+        let messages = vec![ChatMessage::new("user", "hi")];
+        start_stream(&registry, recorder.clone(), "req".to_string(), targets, messages, Vec::new())
+            .unwrap()
+            .await;
+        recorder
+    }
+
+    #[tokio::test]
+    async fn falls_back_in_order_past_transient_and_unreachable_targets() {
+        let recorder = run(vec![
+            target("a", vec![Step::Fail(vec![], status(503))]),
+            target("b", vec![Step::Fail(vec![], ProviderError::Unreachable("connection refused".into()))]),
+            target("c", vec![Step::Reply(vec!["Hel", "lo"], vec![])]),
+        ]).await;
+
+        let selected = recorder.payloads(MODEL_EVENT);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0]["model"], "c");
+        assert_eq!(selected[0]["attempt"], 2);
+        let skipped: Vec<_> = selected[0]["skipped"].as_array().unwrap().iter().map(|s| s["model"].clone()).collect();
+        assert_eq!(skipped, ["a", "b"]);
+
+        assert_eq!(recorder.chunks(), ["Hel", "lo"]);
+        assert_eq!(recorder.last()["done"], true);
+        assert!(recorder.last().get("error").is_none());
+        assert_eq!(*recorder.usage.lock().unwrap(), ["c"]);
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_end_the_chain() {
+        let recorder = run(vec![
+            target("a", vec![Step::Fail(vec![], ProviderError::Other("bad request".into()))]),
+            target("b", vec![Step::Reply(vec!["unused"], vec![])]),
+        ]).await;
+
+        assert!(recorder.chunks().is_empty());
+        assert_eq!(recorder.last()["error"], "bad request");
+    }
+
+    #[tokio::test]
+    async fn answered_target_is_not_abandoned_on_error() {
+        let recorder = run(vec![
+            target("a", vec![Step::Fail(vec!["partial"], status(500))]),
+            target("b", vec![Step::Reply(vec!["spliced"], vec![])]),
+        ]).await;
+
+        assert_eq!(recorder.chunks(), ["partial"]);
+        assert_eq!(recorder.payloads(MODEL_EVENT).len(), 1);
+        assert_eq!(recorder.last()["error"], "HTTP 500");
+    }
+
+    #[tokio::test]
+    async fn empty_answers_fall_through() {
+        let recorder = run(vec![
+            target("a", vec![Step::Reply(vec![], vec![])]),
+            target("b", vec![Step::Reply(vec!["ok"], vec![])]),
+        ]).await;
+
+        let selected = recorder.payloads(MODEL_EVENT);
+        assert_eq!(selected[0]["skipped"][0]["error"], "Empty response");
+        assert_eq!(recorder.chunks(), ["ok"]);
+    }
+
+    #[tokio::test]
+    async fn payloads_are_numbered_in_order() {
+        let recorder = run(vec![target("a", vec![Step::Reply(vec!["a", "b", "c"], vec![])])]).await;
+
+        let seqs: Vec<_> = recorder.payloads(STREAM_EVENT).iter().map(|p| p["seq"].as_u64().unwrap()).collect();
+        assert_eq!(seqs, [0, 1, 2, 3]);
+        assert!(recorder.payloads(STREAM_EVENT).iter().all(|p| p["request_id"] == "req"));
+    }
+
+    #[tokio::test]
+    async fn cancel_ends_the_stream_with_a_cancelled_payload() {
+        let recorder = Recorder::default();
+        let registry = StreamRegistry::default();
+        let targets = vec![target("a", vec![Step::Hang])];
+        let task = start_stream(&registry, recorder.clone(), "req".to_string(), targets, Vec::new(), Vec::new()).unwrap();
+        let handle = tokio::spawn(task);
+
+        assert!(registry.cancel("req"));
+        handle.await.unwrap();
+
+        assert_eq!(recorder.last()["cancelled"], true);
+        assert_eq!(recorder.last()["done"], true);
+        assert!(!registry.cancel("req"));
+    }
+
+    #[tokio::test]
+    async fn duplicate_request_ids_are_rejected() {
+        let registry = StreamRegistry::default();
+        let first = start_stream(&registry, Recorder::default(), "req".to_string(), vec![target("a", vec![Step::Hang])], Vec::new(), Vec::new());
+        let second = start_stream(&registry, Recorder::default(), "req".to_string(), vec![target("a", vec![Step::Hang])], Vec::new(), Vec::new());
+
+        assert!(first.is_ok());
+        assert!(second.is_err());
+    }
+
+    #[tokio::test]
+    async fn tool_calls_round_trip_through_the_registry() {
+        let recorder = Recorder::default();
+        let registry = StreamRegistry::default();
+        let call = ToolCall { id: String::new(), name: "ls".to_string(), arguments: serde_json::json!({ "path": "." }) };
+        let targets = vec![target("a", vec![
+            Step::Reply(vec!["Checking"], vec![call]),
+            Step::Reply(vec!["Found a.txt"], vec![]),
+        ])];
+        let requests = targets[0].provider.requests.clone();
+        let task = start_stream(&registry, recorder.clone(), "req".to_string(), targets, vec![ChatMessage::new("user", "ls")], Vec::new()).unwrap();
+        let handle = tokio::spawn(task);
+
+        let call_id = loop {
+            if let Some(payload) = recorder.payloads(TOOL_EVENT).pop() {
+                break payload["call_id"].as_str().unwrap().to_string();
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert_eq!(call_id, "req_call_1");
+        assert!(registry.resolve_tool(&call_id, serde_json::json!("a.txt")));
+        handle.await.unwrap();
+
+        // The follow-up request carries the assistant's call and the tool's answer
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let turns = &requests[1].messages;
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[1].role, "assistant");
+        assert_eq!(turns[1].content, "Checking");
+        assert_eq!(turns[1].tool_calls.as_ref().unwrap()[0].id, "req_call_1");
+        assert_eq!(turns[2].role, "tool");
+        assert_eq!(turns[2].content, "a.txt");
+        assert_eq!(turns[2].tool_call_id.as_deref(), Some("req_call_1"));
+
+        assert_eq!(recorder.chunks(), ["Checking", "Found a.txt"]);
+        assert!(recorder.last().get("error").is_none());
+        assert_eq!(recorder.payloads(MODEL_EVENT).len(), 1);
+        assert_eq!(recorder.usage.lock().unwrap().len(), 2);
+    }
+}
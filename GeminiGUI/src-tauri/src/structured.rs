@@ -0,0 +1,109 @@
+// ============================================================================
+// STRUCTURED OUTPUT
+// ============================================================================
+//
+// `prompt_structured` asks a model for JSON that follows a schema (Gemini
+// `responseSchema` / Ollama `format`) and validates the answer here rather
+// than trusting the backend. An invalid answer is sent back with the
+// validation errors, a bounded number of times - the Rust replacement for
+// scraping plans out of free text with `Clean-Json` in AgentSwarm.psm1.
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::http::HttpState;
+use crate::providers::{ChatMessage, ChatRequest, LlmProvider, ProviderConfig};
+use crate::secrets::SecretStore;
+
+/// Total tries, including the first answer
+const MAX_ATTEMPTS: usize = 3;
+
+/// How many validation errors are quoted back to the model
+const MAX_REPORTED_ERRORS: usize = 5;
+
+/// Parse a model answer as JSON, tolerating a surrounding Markdown code fence
+fn parse_answer(answer: &str) -> Result<Value, String> {
+    let trimmed = answer.trim();
+    let unfenced = trimmed.strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(|body| body.trim_start_matches("json").trim())
+        .unwrap_or(trimmed);
+    serde_json::from_str(unfenced).map_err(|e| format!("the answer is not valid JSON ({})", e))
+}
+
+/// The parsed answer if it satisfies the schema, otherwise a description of what is wrong
+fn check_answer(validator: &jsonschema::Validator, answer: &str) -> Result<Value, String> {
+    let value = parse_answer(answer)?;
+    let errors: Vec<String> = validator.iter_errors(&value)
+        .take(MAX_REPORTED_ERRORS)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() { e.to_string() } else { format!("{}: {}", path, e) }
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn retry_message(problem: &str) -> ChatMessage {
+    ChatMessage::new("user", format!(
+        "Your previous answer does not match the required JSON schema: {}. \
+         Reply again with only the corrected JSON.",
+        problem
+    ))
+}
+
+/// Run a completion whose answer must be JSON matching `json_schema`. Returns
+/// the validated JSON value, or an error after `MAX_ATTEMPTS` invalid answers.
+#[tauri::command]
+pub(crate) async fn prompt_structured(
+    http: State<'_, HttpState>,
+    secrets: State<'_, SecretStore>,
+    provider: ProviderConfig,
+    model: String,
+    messages: Vec<ChatMessage>,
+    json_schema: Value,
+) -> Result<Value, String> {
+    let validator = jsonschema::validator_for(&json_schema).map_err(|e| format!("Invalid JSON schema: {}", e))?;
+    let provider = provider.with_json_schema(&json_schema).build(http.client(), &secrets, &model)?;
+
+    let mut request = ChatRequest { model, messages, tools: Vec::new() };
+    let mut problem = String::new();
+    for _ in 0..MAX_ATTEMPTS {
+        let answer = provider.chat(&request).await?;
+        match check_answer(&validator, &answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => problem = e,
+        }
+        request.messages.push(ChatMessage::new("assistant", answer));
+        request.messages.push(retry_message(&problem));
+    }
+
+    Err(format!("No valid structured answer after {} attempts: {}", MAX_ATTEMPTS, problem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_answers_against_the_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "steps": { "type": "array", "items": { "type": "string" } } },
+            "required": ["steps"]
+        });
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let fenced = "```json\n{\"steps\": [\"plan\", \"execute\"]}\n```";
+        assert_eq!(check_answer(&validator, fenced).unwrap(), serde_json::json!({ "steps": ["plan", "execute"] }));
+
+        let problem = check_answer(&validator, "{\"steps\": [1]}").unwrap_err();
+        assert!(problem.starts_with("/steps/0: "), "{}", problem);
+
+        assert!(check_answer(&validator, "Sure! Here is the plan").unwrap_err().contains("not valid JSON"));
+    }
+}
@@ -0,0 +1,270 @@
+// ============================================================================
+// USAGE ACCOUNTING
+// ============================================================================
+//
+// Every completed stream that reports token counts is emitted on
+// `stream-usage` and appended to `usage_ledger.jsonl` in the app data dir,
+// one JSON record per line. `get_usage_report` aggregates the ledger per
+// model and/or per (local) day and prices it with the table in settings.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, State, Window};
+
+use crate::providers::Usage;
+use crate::settings::read_settings;
+
+const USAGE_EVENT: &str = "stream-usage";
+
+/// USD price per million tokens
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub(crate) struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Price for `model`: an exact entry, else the longest entry the name starts
+/// with (so `gemini-1.5-pro` also prices `gemini-1.5-pro-002`)
+fn price_for<'a>(prices: &'a HashMap<String, ModelPrice>, model: &str) -> Option<&'a ModelPrice> {
+    prices.get(model).or_else(|| {
+        prices.iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, price)| price)
+    })
+}
+
+/// Estimated cost in USD; models without a price count as free
+fn estimate_cost(prices: &HashMap<String, ModelPrice>, model: &str, usage: &Usage) -> f64 {
+    price_for(prices, model).map_or(0.0, |price| {
+        (usage.prompt_tokens as f64 * price.input_per_million
+            + usage.completion_tokens as f64 * price.output_per_million) / 1_000_000.0
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct UsageRecord {
+    /// Unix seconds
+    timestamp: i64,
+    provider: String,
+    model: String,
+    #[serde(flatten)]
+    usage: Usage,
+}
+
+impl UsageRecord {
+    fn day(&self) -> Option<NaiveDate> {
+        DateTime::from_timestamp(self.timestamp, 0).map(|t| t.with_timezone(&Local).date_naive())
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct UsagePayload {
+    request_id: String,
+    provider: String,
+    model: String,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    estimated_cost: f64,
+}
+
+/// Append-only usage ledger (Tauri managed state)
+pub(crate) struct UsageLedger {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl UsageLedger {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            path: dir.join("usage_ledger.jsonl"),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn append(&self, record: &UsageRecord) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap();
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+        }
+        let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| format!("Failed to write usage ledger: {}", e))
+    }
+
+    /// All records; lines that do not parse (e.g. a torn last write) are skipped
+    fn records(&self) -> Result<Vec<UsageRecord>, String> {
+        let _guard = self.lock.lock().unwrap();
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| format!("Failed to read usage ledger: {}", e))?;
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+}
+
+/// Append the usage of a finished stream to the ledger, then emit it. The
+/// ledger comes first: a closed window must not lose the record.
+pub(crate) fn record_usage(window: &Window, request_id: &str, provider: &str, model: &str, usage: Usage) -> Result<(), String> {
+    let appended = match window.try_state::<UsageLedger>() {
+        Some(ledger) => ledger.append(&UsageRecord {
+            timestamp: chrono::Utc::now().timestamp(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            usage,
+        }),
+        None => Ok(()),
+    };
+
+    let estimated_cost = estimate_cost(&read_settings().prices, model, &usage);
+    // Nobody may be listening any more; that is not a failure
+    let _ = window.emit(USAGE_EVENT, UsagePayload {
+        request_id: request_id.to_string(),
+        provider: provider.to_string(),
+        model: model.to_string(),
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        estimated_cost,
+    });
+    appended
+}
+
+/// Inclusive range of local days; an open end is unbounded
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub(crate) struct UsageRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl UsageRange {
+    fn contains(&self, day: NaiveDate) -> bool {
+        self.from.map_or(true, |from| day >= from) && self.to.map_or(true, |to| day <= to)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UsageGroupBy {
+    Model,
+    Day,
+    ModelDay,
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct UsageReportRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    requests: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    estimated_cost: f64,
+}
+
+/// Report rows are keyed by (day, provider, model), with `None` for ungrouped parts
+type ReportKey = (Option<NaiveDate>, Option<String>, Option<String>);
+
+fn build_report(
+    records: &[UsageRecord],
+    prices: &HashMap<String, ModelPrice>,
+    range: &UsageRange,
+    group_by: UsageGroupBy,
+) -> Vec<UsageReportRow> {
+    // BTreeMap keeps rows sorted by day, then provider/model
+    let mut rows: BTreeMap<ReportKey, UsageReportRow> = BTreeMap::new();
+
+    for record in records {
+        let Some(day) = record.day().filter(|day| range.contains(*day)) else {
+            continue;
+        };
+        let by_day = matches!(group_by, UsageGroupBy::Day | UsageGroupBy::ModelDay).then_some(day);
+        let by_model = matches!(group_by, UsageGroupBy::Model | UsageGroupBy::ModelDay);
+        let provider = by_model.then(|| record.provider.clone());
+        let model = by_model.then(|| record.model.clone());
+
+        let row = rows.entry((by_day, provider.clone(), model.clone())).or_insert_with(|| UsageReportRow {
+            day: by_day,
+            provider,
+            model,
+            ..Default::default()
+        });
+        row.requests += 1;
+        row.prompt_tokens += record.usage.prompt_tokens;
+        row.completion_tokens += record.usage.completion_tokens;
+        row.estimated_cost += estimate_cost(prices, &record.model, &record.usage);
+    }
+
+    rows.into_values().collect()
+}
+
+/// Token counts and estimated cost from the ledger, grouped per model, per day or both
+#[tauri::command]
+pub(crate) fn get_usage_report(
+    ledger: State<'_, UsageLedger>,
+    range: Option<UsageRange>,
+    group_by: UsageGroupBy,
+) -> Result<Vec<UsageReportRow>, String> {
+    let records = ledger.records()?;
+    Ok(build_report(&records, &read_settings().prices, &range.unwrap_or_default(), group_by))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(day: &str, model: &str, prompt_tokens: u64, completion_tokens: u64) -> UsageRecord {
+        let noon = NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+            .and_hms_opt(12, 0, 0).unwrap()
+            .and_local_timezone(Local).unwrap();
+        UsageRecord {
+            timestamp: noon.timestamp(),
+            provider: "gemini".to_string(),
+            model: model.to_string(),
+            usage: Usage { prompt_tokens, completion_tokens },
+        }
+    }
+
+    #[test]
+    fn groups_and_prices_records() {
+        let records = vec![
+            record("2026-03-01", "gemini-1.5-pro-002", 1_000_000, 0),
+            record("2026-03-01", "llama3", 10, 20),
+            record("2026-03-02", "gemini-1.5-pro-002", 0, 500_000),
+            record("2026-03-05", "gemini-1.5-pro-002", 1, 1),
+        ];
+        let prices = HashMap::from([
+            ("gemini-1.5-pro".to_string(), ModelPrice { input_per_million: 1.25, output_per_million: 5.0 }),
+        ]);
+        let range = UsageRange {
+            from: NaiveDate::from_ymd_opt(2026, 3, 1),
+            to: NaiveDate::from_ymd_opt(2026, 3, 2),
+        };
+
+        let by_model = build_report(&records, &prices, &range, UsageGroupBy::Model);
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0].model.as_deref(), Some("gemini-1.5-pro-002"));
+        assert_eq!(by_model[0].requests, 2);
+        assert_eq!(by_model[0].estimated_cost, 1.25 + 2.5);
+        assert_eq!(by_model[1].estimated_cost, 0.0);
+
+        let by_day = build_report(&records, &prices, &range, UsageGroupBy::Day);
+        assert_eq!(by_day.iter().map(|r| r.requests).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(by_day[0].model, None);
+    }
+}
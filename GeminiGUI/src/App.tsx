@@ -1,6 +1,6 @@
 // Store & Hooks
 import { useAppStore, selectCurrentMessages } from './store/useAppStore';
-import { useAppTheme, useStreamListeners, useEnvLoader } from './hooks';
+import { useAppTheme, useStreamListeners, useGeminiModels } from './hooks';
 
 // Components
 import { SettingsModal } from './components/SettingsModal';
@@ -40,7 +40,6 @@ function App() {
   const count = useAppStore((state) => state.count);
   const currentSessionId = useAppStore((state) => state.currentSessionId);
   const sessions = useAppStore((state) => state.sessions);
-  const settings = useAppStore((state) => state.settings);
 
   const increment = useAppStore((state) => state.increment);
   const decrement = useAppStore((state) => state.decrement);
@@ -54,7 +53,7 @@ function App() {
 
   const currentMessages = useAppStore(selectCurrentMessages);
   const { toggleTheme, isDark } = useAppTheme();
-  useEnvLoader();
+  const { hasApiKey } = useGeminiModels();
 
   // ========================================
   // Initialization & Tauri Check
@@ -232,10 +231,10 @@ function App() {
   const logoSrc = useMemo(() => (isDark ? '/logodark.webp' : '/logolight.webp'), [isDark]);
   const headerSpanClass = useMemo(() => (isDark ? 'text-white' : 'text-gray-800'), [isDark]);
   const statusBadgeState = useMemo(() => 
-    settings.geminiApiKey 
+    hasApiKey 
       ? { className: 'status-approved bg-green-500/10 border-green-500/30 text-green-400', text: STATUS.GEMINI_READY }
       : { className: 'status-pending bg-yellow-500/10 border-yellow-500/30 text-yellow-400', text: 'Local Only' },
-  [settings.geminiApiKey]);
+  [hasApiKey]);
 
   return (
     <main className={cn(
@@ -1,7 +1,9 @@
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { Shield, Check, X, RefreshCw, ToggleLeft, ToggleRight, Clock } from 'lucide-react';
+import { TAURI_EVENTS } from '../constants';
 
 interface BridgeRequest {
     id: string;
@@ -26,15 +28,24 @@ export const BridgePanel: React.FC = () => {
             setLastUpdate(new Date());
             return result;
         },
-        // Adaptive polling: faster when there are pending requests
-        refetchInterval: (query) => {
-            const data = query.state.data;
-            const hasPending = data?.requests.some(r => r.status === 'pending');
-            return hasPending ? 2000 : 10000; // 2s if pending, 10s otherwise
-        },
         staleTime: 1000,
     });
 
+    // The backend watches bridge.json and announces every change, so refetch
+    // on those events instead of polling
+    useEffect(() => {
+        const refresh = () => {
+            queryClient.invalidateQueries({ queryKey: ['bridge-state'] });
+        };
+        const unlistenRequest = listen(TAURI_EVENTS.BRIDGE_REQUEST, refresh);
+        const unlistenResolved = listen(TAURI_EVENTS.BRIDGE_RESOLVED, refresh);
+
+        return () => {
+            unlistenRequest.then((f) => f());
+            unlistenResolved.then((f) => f());
+        };
+    }, [queryClient]);
+
     // Toggle auto-approve mutation
     const toggleMutation = useMutation({
         mutationFn: async (enabled: boolean) => {
@@ -1,5 +1,9 @@
 import React, { memo } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { useQueryClient } from '@tanstack/react-query';
 import { useAppStore } from '../store/useAppStore';
+import { isValidApiKey } from '../utils/validators';
+import { QUERY_KEYS, TAURI_COMMANDS } from '../constants';
 import { X, Save } from 'lucide-react';
 
 interface SettingsModalProps {
@@ -8,16 +12,37 @@ interface SettingsModalProps {
 }
 
 const SettingsModalComponent: React.FC<SettingsModalProps> = ({ isOpen, onClose }) => {
-  const { settings, updateSettings } = useAppStore();
+  const { settings, updateSettings, hasGeminiKey } = useAppStore();
+  const queryClient = useQueryClient();
   const [localSettings, setLocalSettings] = React.useState(settings);
+  // Write-only: the key goes to the backend secret store on save and is never read back
+  const [apiKey, setApiKey] = React.useState('');
+  const [apiKeyError, setApiKeyError] = React.useState<string | null>(null);
 
   React.useEffect(() => {
-    if (isOpen) setLocalSettings(settings);
+    if (isOpen) {
+      setLocalSettings(settings);
+      setApiKey('');
+      setApiKeyError(null);
+    }
   }, [isOpen, settings]);
 
   if (!isOpen) return null;
 
-  const handleSave = () => {
+  const handleSave = async () => {
+    if (apiKey) {
+      if (!isValidApiKey(apiKey)) {
+        setApiKeyError('Nieprawidłowy format klucza API');
+        return;
+      }
+      try {
+        await invoke(TAURI_COMMANDS.SET_SECRET, { name: 'GEMINI_API_KEY', value: apiKey });
+        await queryClient.invalidateQueries({ queryKey: [QUERY_KEYS.GEMINI_API_KEY] });
+      } catch (e) {
+        setApiKeyError(e instanceof Error ? e.message : String(e));
+        return;
+      }
+    }
     updateSettings(localSettings);
     onClose();
   };
@@ -49,12 +74,13 @@ const SettingsModalComponent: React.FC<SettingsModalProps> = ({ isOpen, onClose
           <div className="flex flex-col gap-2">
             <label className="text-sm text-[var(--matrix-text-dim)] font-mono">Klucz API Google Gemini</label>
             <input
-              value={localSettings.geminiApiKey}
-              onChange={(e) => setLocalSettings({...localSettings, geminiApiKey: e.target.value})}
+              value={apiKey}
+              onChange={(e) => { setApiKey(e.target.value); setApiKeyError(null); }}
               className="matrix-input p-2 rounded text-sm font-mono"
               type="password"
-              placeholder="AIza..."
+              placeholder={hasGeminiKey ? 'Klucz zapisany - wpisz nowy, aby go zastąpić' : 'AIza...'}
             />
+            {apiKeyError && <span className="text-xs text-red-400 font-mono">{apiKeyError}</span>}
           </div>
 
           <div className="flex flex-col gap-2">
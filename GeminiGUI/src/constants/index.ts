@@ -76,7 +76,6 @@ Używaj tego tylko do bezpiecznego zbierania informacji. Twoja pieśń musi być
 export const DEFAULT_SETTINGS: Settings = {
   ollamaEndpoint: 'http://localhost:11434',
   systemPrompt: DEFAULT_SYSTEM_PROMPT,
-  geminiApiKey: '',
   defaultProvider: 'ollama',
   useSwarm: false,
 };
@@ -117,6 +116,8 @@ export const TAURI_EVENTS = {
   OLLAMA_EVENT: 'ollama-event',
   SWARM_DATA: 'swarm-data',
   GEMINI_STREAM: 'gemini-stream',
+  BRIDGE_REQUEST: 'bridge-request',
+  BRIDGE_RESOLVED: 'bridge-resolved',
 } as const;
 
 // ============================================================================
@@ -147,6 +148,10 @@ export const TAURI_COMMANDS = {
   GET_ENV_VARS: 'get_env_vars',
   START_OLLAMA_SERVER: 'start_ollama_server',
 
+  // Secrets
+  HAS_SECRET: 'has_secret',
+  SET_SECRET: 'set_secret',
+
   // Memory
   GET_AGENT_MEMORIES: 'get_agent_memories',
   ADD_AGENT_MEMORY: 'add_agent_memory',
@@ -162,6 +167,7 @@ export const TAURI_COMMANDS = {
 
 export const QUERY_KEYS = {
   GEMINI_MODELS: 'gemini-models',
+  GEMINI_API_KEY: 'gemini-api-key',
   OLLAMA_MODELS: 'ollama-models',
   BRIDGE_STATE: 'bridge-state',
   AGENT_MEMORIES: 'agent-memories',
@@ -9,6 +9,5 @@ export { useAppTheme, default as useAppThemeDefault } from './useAppTheme';
 export { useStreamListeners, default as useStreamListenersDefault } from './useStreamListeners';
 export { useGeminiModels, default as useGeminiModelsDefault } from './useGeminiModels';
 export { useOllamaModels, default as useOllamaModelsDefault } from './useOllamaModels';
-export { useEnvLoader, default as useEnvLoaderDefault } from './useEnvLoader';
 export { useHotkey, isHotkeyPressed, default as useHotkeyDefault } from './useHotkey';
 export { useKeyboardShortcuts, default as useKeyboardShortcutsDefault } from './useKeyboardShortcuts';
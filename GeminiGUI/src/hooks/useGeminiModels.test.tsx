@@ -3,7 +3,7 @@
  * @module hooks/__tests__/useGeminiModels.test.ts
  *
  * Comprehensive test suite for the useGeminiModels hook.
- * Tests loading states, error handling, API key validation, and refetching.
+ * Tests loading states, error handling, API key detection, and refetching.
  */
 
 import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
@@ -12,8 +12,7 @@ import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import type { ReactNode } from 'react';
 
 import { useGeminiModels } from './useGeminiModels';
-import { useAppStore } from '../store/useAppStore';
-import { FALLBACK_MODELS, TAURI_COMMANDS } from '../constants';
+import { FALLBACK_MODELS, QUERY_KEYS, TAURI_COMMANDS } from '../constants';
 
 // ============================================================================
 // MOCKS
@@ -27,11 +26,6 @@ vi.mock('@tauri-apps/api/core', () => ({
 // Import the mocked invoke for use in tests
 import { invoke } from '@tauri-apps/api/core';
 
-// Mock the zustand store
-vi.mock('../store/useAppStore', () => ({
-  useAppStore: vi.fn(),
-}));
-
 // ============================================================================
 // TEST SETUP
 // ============================================================================
@@ -50,6 +44,22 @@ function createTestQueryClient() {
   });
 }
 
+/**
+ * Answer `has_secret` with `hasKey` and `get_gemini_models` with `models`
+ * (rejecting if it is an Error)
+ */
+function mockBackend(hasKey: boolean, models?: unknown) {
+  (invoke as any).mockImplementation((cmd: string) => {
+    if (cmd === TAURI_COMMANDS.HAS_SECRET) {
+      return Promise.resolve(hasKey);
+    }
+    if (cmd === TAURI_COMMANDS.GET_GEMINI_MODELS) {
+      return models instanceof Error ? Promise.reject(models) : Promise.resolve(models);
+    }
+    return Promise.resolve(null);
+  });
+}
+
 /**
  * Wrapper component that provides React Query context
  */
@@ -82,18 +92,11 @@ describe('useGeminiModels', () => {
   // ========================================================================
 
   it('should return loading state initially', async () => {
-    // Setup: Mock store to return API key
-    const mockApiKey = 'test-api-key-123';
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
-    });
-
-    // Setup: Mock invoke to delay response
-    (invoke as any).mockImplementation(
-      () => new Promise(resolve => setTimeout(() => resolve(['gemini-model']), 100))
+    // Setup: Backend has a key, model list arrives with a delay
+    (invoke as any).mockImplementation((cmd: string) =>
+      cmd === TAURI_COMMANDS.HAS_SECRET
+        ? Promise.resolve(true)
+        : new Promise(resolve => setTimeout(() => resolve(['gemini-model']), 100))
     );
 
     // Act
@@ -105,7 +108,6 @@ describe('useGeminiModels', () => {
     expect(result.current.isLoading).toBe(true);
     expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
     expect(result.current.error).toBeNull();
-    expect(result.current.hasApiKey).toBe(true);
 
     // Wait for loading to complete
     await waitFor(() => {
@@ -114,6 +116,7 @@ describe('useGeminiModels', () => {
 
     // Assert: After loading, should have models
     expect(result.current.models).toEqual(['gemini-model']);
+    expect(result.current.hasApiKey).toBe(true);
   });
 
   // ========================================================================
@@ -121,13 +124,8 @@ describe('useGeminiModels', () => {
   // ========================================================================
 
   it('should return fallback models when no API key is present', async () => {
-    // Setup: Mock store to return empty API key
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: '' },
-      };
-      return selector(state);
-    });
+    // Setup: Backend has no key
+    mockBackend(false);
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -142,9 +140,6 @@ describe('useGeminiModels', () => {
     expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
     expect(result.current.hasApiKey).toBe(false);
     expect(result.current.error).toBeNull();
-
-    // Verify invoke was NOT called (no API key)
-    expect(invoke).not.toHaveBeenCalled();
   });
 
   // ========================================================================
@@ -153,17 +148,8 @@ describe('useGeminiModels', () => {
 
   it('should fetch models when API key is present', async () => {
     // Setup
-    const mockApiKey = 'sk-proj-valid-key-123';
     const mockModels = ['gemini-2.0-flash', 'gemini-1.5-pro', 'gemini-1.5-flash'];
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
-    });
-
-    (invoke as any).mockResolvedValue(mockModels);
+    mockBackend(true, mockModels);
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -175,10 +161,11 @@ describe('useGeminiModels', () => {
       expect(result.current.isLoading).toBe(false);
     });
 
-    expect(invoke).toHaveBeenCalledWith(
-      TAURI_COMMANDS.GET_GEMINI_MODELS,
-      { apiKey: mockApiKey }
-    );
+    expect(invoke).toHaveBeenCalledWith(TAURI_COMMANDS.HAS_SECRET, {
+      name: 'GEMINI_API_KEY',
+    });
+    // The key is never sent from the frontend
+    expect(invoke).toHaveBeenCalledWith(TAURI_COMMANDS.GET_GEMINI_MODELS);
 
     expect(result.current.models).toEqual(mockModels);
     expect(result.current.hasApiKey).toBe(true);
@@ -191,17 +178,7 @@ describe('useGeminiModels', () => {
 
   it.skip('should return error state on fetch failure', async () => {
     // Setup
-    const mockApiKey = 'sk-proj-invalid-key';
-    const mockError = new Error('401: Invalid API key');
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
-    });
-
-    (invoke as any).mockRejectedValue(mockError);
+    mockBackend(true, new Error('401: Invalid API key'));
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -226,24 +203,12 @@ describe('useGeminiModels', () => {
 
   it.skip('should refetch models when refetch is called', async () => {
     // Setup
-    const mockApiKey = 'sk-proj-test-key';
     const initialModels = ['gemini-1.5-flash'];
     const refetchedModels = ['gemini-2.0-flash', 'gemini-1.5-pro'];
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
-    });
-
-    // First call returns initial models, second call returns refetched models
-    (invoke as any)
-      .mockResolvedValueOnce(initialModels)
-      .mockResolvedValueOnce(refetchedModels);
+    mockBackend(true, initialModels);
 
     // Act
-    const { result, rerender } = renderHook(() => useGeminiModels(), {
+    const { result } = renderHook(() => useGeminiModels(), {
       wrapper: createWrapper(queryClient),
     });
 
@@ -255,18 +220,13 @@ describe('useGeminiModels', () => {
     expect(result.current.models).toEqual(initialModels);
 
     // Act: Call refetch
+    mockBackend(true, refetchedModels);
     result.current.refetch();
 
-    // Assert: Should be loading again
-    expect(result.current.isLoading).toBe(true);
-
     // Wait for refetch to complete
     await waitFor(() => {
-      expect(result.current.isLoading).toBe(false);
+      expect(result.current.models).toEqual(refetchedModels);
     });
-
-    expect(result.current.models).toEqual(refetchedModels);
-    expect(invoke).toHaveBeenCalledTimes(2);
   });
 
   // ========================================================================
@@ -275,14 +235,7 @@ describe('useGeminiModels', () => {
 
   it('should return correct hasApiKey boolean', async () => {
     // Setup: Test with API key
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: 'sk-proj-key-exists' },
-      };
-      return selector(state);
-    });
-
-    (invoke as any).mockResolvedValue(['gemini-model']);
+    mockBackend(true, ['gemini-model']);
 
     // Act
     const { result: resultWithKey } = renderHook(() => useGeminiModels(), {
@@ -298,13 +251,7 @@ describe('useGeminiModels', () => {
     // Setup: Test without API key
     vi.clearAllMocks();
     queryClient.clear();
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: '' },
-      };
-      return selector(state);
-    });
+    mockBackend(false);
 
     // Act
     const { result: resultWithoutKey } = renderHook(() => useGeminiModels(), {
@@ -319,36 +266,16 @@ describe('useGeminiModels', () => {
   });
 
   // ========================================================================
-  // Test 7: Refetches when API key changes
+  // Test 7: Refetches when the backend gains a key
   // ========================================================================
 
-  it.skip('should refetch when API key changes', async () => {
-    // Setup: Initial API key
-    const initialKey = 'sk-proj-key-1';
-    const newKey = 'sk-proj-key-2';
-    const initialModels = ['gemini-1.5-flash'];
+  it.skip('should refetch when the API key is stored', async () => {
+    // Setup: No key yet
     const newModels = ['gemini-2.0-flash'];
+    mockBackend(false);
 
-    (useAppStore as any)
-      .mockImplementationOnce((selector: any) => {
-        const state = {
-          settings: { geminiApiKey: initialKey },
-        };
-        return selector(state);
-      })
-      .mockImplementationOnce((selector: any) => {
-        const state = {
-          settings: { geminiApiKey: newKey },
-        };
-        return selector(state);
-      });
-
-    (invoke as any)
-      .mockResolvedValueOnce(initialModels)
-      .mockResolvedValueOnce(newModels);
-
-    // Act: Render with initial key
-    const { result, rerender } = renderHook(() => useGeminiModels(), {
+    // Act: Render without key
+    const { result } = renderHook(() => useGeminiModels(), {
       wrapper: createWrapper(queryClient),
     });
 
@@ -356,31 +283,16 @@ describe('useGeminiModels', () => {
       expect(result.current.isLoading).toBe(false);
     });
 
-    expect(result.current.models).toEqual(initialModels);
-
-    // Act: Simulate API key change
-    vi.clearAllMocks();
-    queryClient.clear();
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: newKey },
-      };
-      return selector(state);
-    });
-
-    (invoke as any).mockResolvedValue(newModels);
-
-    // Rerender with new key
-    rerender();
+    expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
 
-    await waitFor(() => {
-      expect(invoke).toHaveBeenCalled();
-    });
+    // Act: The key gets stored and the key query is invalidated
+    mockBackend(true, newModels);
+    await queryClient.invalidateQueries({ queryKey: [QUERY_KEYS.GEMINI_API_KEY] });
 
     await waitFor(() => {
       expect(result.current.models).toEqual(newModels);
     });
+    expect(result.current.hasApiKey).toBe(true);
   });
 
   // ========================================================================
@@ -388,18 +300,8 @@ describe('useGeminiModels', () => {
   // ========================================================================
 
   it('should return fallback models when API returns empty array', async () => {
-    // Setup
-    const mockApiKey = 'sk-proj-test-key';
-
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
-    });
-
-    // Mock API to return empty array
-    (invoke as any).mockResolvedValue([]);
+    // Setup: Mock API to return empty array
+    mockBackend(true, []);
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -421,16 +323,29 @@ describe('useGeminiModels', () => {
 
   it('should return fallback models when API returns null or undefined', async () => {
     // Setup
-    const mockApiKey = 'sk-proj-test-key';
+    mockBackend(true, null);
 
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: mockApiKey },
-      };
-      return selector(state);
+    // Act
+    const { result } = renderHook(() => useGeminiModels(), {
+      wrapper: createWrapper(queryClient),
     });
 
-    (invoke as any).mockResolvedValue(null);
+    // Assert
+    await waitFor(() => {
+      expect(result.current.isLoading).toBe(false);
+    });
+
+    expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
+    expect(result.current.error).toBeNull();
+  });
+
+  // ========================================================================
+  // Test 10: Does not fetch models when API key is missing
+  // ========================================================================
+
+  it('should not fetch models when API key is missing', async () => {
+    // Setup
+    mockBackend(false);
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -442,22 +357,17 @@ describe('useGeminiModels', () => {
       expect(result.current.isLoading).toBe(false);
     });
 
+    expect(invoke).not.toHaveBeenCalledWith(TAURI_COMMANDS.GET_GEMINI_MODELS);
     expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
-    expect(result.current.error).toBeNull();
   });
 
   // ========================================================================
-  // Test 10: Does not call invoke when API key is missing
+  // Test 11: Treats a failed key check as no key
   // ========================================================================
 
-  it('should not call invoke when API key is missing', async () => {
+  it('should fall back when the key check fails', async () => {
     // Setup
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: '' },
-      };
-      return selector(state);
-    });
+    (invoke as any).mockRejectedValue(new Error('secret store locked'));
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -469,24 +379,17 @@ describe('useGeminiModels', () => {
       expect(result.current.isLoading).toBe(false);
     });
 
-    expect(invoke).not.toHaveBeenCalled();
+    expect(result.current.hasApiKey).toBe(false);
     expect(result.current.models).toEqual(FALLBACK_MODELS.gemini);
   });
 
   // ========================================================================
-  // Test 11: Returns expected interface shape
+  // Test 12: Returns expected interface shape
   // ========================================================================
 
   it('should return correct interface shape', async () => {
     // Setup
-    (useAppStore as any).mockImplementation((selector: any) => {
-      const state = {
-        settings: { geminiApiKey: 'test-key' },
-      };
-      return selector(state);
-    });
-
-    (invoke as any).mockResolvedValue(['gemini-model']);
+    mockBackend(true, ['gemini-model']);
 
     // Act
     const { result } = renderHook(() => useGeminiModels(), {
@@ -3,10 +3,10 @@
  * @module hooks/useGeminiModels
  *
  * Fetches available Gemini models from the API.
- * Falls back to default models if API key not set.
+ * Falls back to default models if the backend has no API key.
  */
 
-import { useMemo } from 'react';
+import { useEffect, useMemo } from 'react';
 import { useQuery } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../store/useAppStore';
@@ -29,7 +29,29 @@ interface UseGeminiModelsReturn {
  * ```
  */
 export const useGeminiModels = (): UseGeminiModelsReturn => {
-  const geminiApiKey = useAppStore((state) => state.settings.geminiApiKey);
+  // The key itself never leaves the backend; only whether one is set
+  const { data: hasApiKey } = useQuery({
+    queryKey: [QUERY_KEYS.GEMINI_API_KEY],
+    queryFn: async (): Promise<boolean> => {
+      try {
+        return !!(await invoke<boolean>(TAURI_COMMANDS.HAS_SECRET, {
+          name: 'GEMINI_API_KEY',
+        }));
+      } catch (error) {
+        console.error('[useGeminiModels] Failed to check API key:', error);
+        return false;
+      }
+    },
+    refetchOnWindowFocus: false,
+  });
+
+  // Keep the store selectors in step with the backend's answer
+  const setHasGeminiKey = useAppStore((state) => state.setHasGeminiKey);
+  useEffect(() => {
+    if (hasApiKey !== undefined) {
+      setHasGeminiKey(hasApiKey);
+    }
+  }, [hasApiKey, setHasGeminiKey]);
 
   const {
     data: models,
@@ -37,20 +59,19 @@ export const useGeminiModels = (): UseGeminiModelsReturn => {
     error,
     refetch,
   } = useQuery({
-    queryKey: [QUERY_KEYS.GEMINI_MODELS, geminiApiKey],
+    queryKey: [QUERY_KEYS.GEMINI_MODELS, hasApiKey],
     queryFn: async (): Promise<string[]> => {
       console.log('[useGeminiModels] Fetching models...');
 
       // No API key - return fallback models
-      if (!geminiApiKey) {
+      if (!hasApiKey) {
         console.warn('[useGeminiModels] No API key - using fallback models');
         return [...FALLBACK_MODELS.gemini];
       }
 
       try {
         const fetchedModels = await invoke<string[]>(
-          TAURI_COMMANDS.GET_GEMINI_MODELS,
-          { apiKey: geminiApiKey }
+          TAURI_COMMANDS.GET_GEMINI_MODELS
         );
 
         console.log('[useGeminiModels] Models loaded:', fetchedModels);
@@ -65,7 +86,7 @@ export const useGeminiModels = (): UseGeminiModelsReturn => {
         return [...FALLBACK_MODELS.gemini];
       }
     },
-    enabled: true,
+    enabled: hasApiKey !== undefined,
     retry: 1,
     staleTime: Infinity, // Keep data fresh forever unless manually invalidated
     refetchOnMount: false, // Don't refetch on every mount
@@ -79,7 +100,7 @@ export const useGeminiModels = (): UseGeminiModelsReturn => {
     isLoading,
     error: error as Error | null,
     refetch,
-    hasApiKey: !!geminiApiKey,
+    hasApiKey: !!hasApiKey,
   };
 };
 
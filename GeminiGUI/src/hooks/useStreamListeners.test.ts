@@ -534,6 +534,27 @@ describe('useStreamListeners', () => {
       expect(onComplete).toHaveBeenCalledTimes(1);
     });
 
+    it('should ignore payloads tagged with another requestId', async () => {
+      renderHook(() =>
+        useStreamListeners({
+          requestId: 'req-1',
+          onChunk,
+          onComplete,
+          onError,
+        })
+      );
+
+      __triggerEvent('ollama-event', { request_id: 'req-2', chunk: 'other', done: false });
+      __triggerEvent('ollama-event', { request_id: 'req-1', chunk: 'mine', done: false });
+      __triggerEvent('swarm-data', { chunk: 'swarm', done: false });
+      __triggerEvent('ollama-event', { request_id: 'req-2', chunk: '', done: true });
+
+      expect(onChunk).toHaveBeenCalledTimes(2);
+      expect(onChunk).toHaveBeenCalledWith('mine');
+      expect(onChunk).toHaveBeenCalledWith('swarm');
+      expect(onComplete).not.toHaveBeenCalled();
+    });
+
     it('should handle edge case: completion without chunks', async () => {
       renderHook(() =>
         useStreamListeners({
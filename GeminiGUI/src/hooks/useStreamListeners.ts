@@ -11,6 +11,8 @@ import { TAURI_EVENTS } from '../constants';
 import type { StreamPayload } from '../types';
 
 interface UseStreamListenersOptions {
+  /** Only handle payloads of this stream; untagged payloads always pass */
+  requestId?: string;
   onChunk: (chunk: string) => void;
   onComplete: () => void;
   onError?: (error: unknown) => void;
@@ -28,6 +30,7 @@ interface UseStreamListenersOptions {
  * ```
  */
 export const useStreamListeners = ({
+  requestId,
   onChunk,
   onComplete,
   onError,
@@ -35,13 +38,16 @@ export const useStreamListeners = ({
   const handleStreamEvent = useCallback(
     (payload: StreamPayload) => {
       const { chunk, done } = payload;
+      if (requestId && payload.request_id && payload.request_id !== requestId) {
+        return;
+      }
       if (!done && chunk) {
         onChunk(chunk);
       } else if (done) {
         onComplete();
       }
     },
-    [onChunk, onComplete]
+    [requestId, onChunk, onComplete]
   );
 
   useEffect(() => {
@@ -81,8 +81,6 @@ export {
   useStreamListenersDefault,
   useGeminiModels,
   useGeminiModelsDefault,
-  useEnvLoader,
-  useEnvLoaderDefault,
   useHotkey,
   isHotkeyPressed,
   useHotkeyDefault,
@@ -142,7 +140,6 @@ export {
   selectSystemPrompt,
   selectDefaultProvider,
   selectUseSwarm,
-  selectGeminiApiKey,
   selectIsAppReady,
   selectApiConfigStatus,
   selectRuntimeSettings,
@@ -69,7 +69,8 @@ export const invoke = async (cmd: string, args: any = {}) => {
   
   const mocks: Record<string, any> = {
     get_bridge_state: { auto_approve: true, requests: [] },
-    get_env_vars: { GEMINI_API_KEY: 'test-key-123' },
+    get_env_vars: {},
+    has_secret: true,
     get_ollama_models: ['llama3-wolf-edition', 'qwen-yennefer-tuned'],
     get_gemini_models: ['gemini-pro-kaer-morhen'],
     get_agent_memories: [],
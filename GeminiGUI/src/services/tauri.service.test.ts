@@ -153,52 +153,46 @@ describe('ModelService', () => {
   });
 
   describe('getGeminiModels', () => {
-    it('should call invoke with GET_GEMINI_MODELS command and apiKey', async () => {
-      const apiKey = 'test-api-key-123';
+    it('should call invoke with GET_GEMINI_MODELS command', async () => {
       const mockModels = ['gemini-2.0-flash-exp', 'gemini-1.5-pro'];
       mockInvoke.mockResolvedValueOnce(mockModels);
 
-      const result = await ModelService.getGeminiModels(apiKey);
+      const result = await ModelService.getGeminiModels();
 
       expect(mockInvoke).toHaveBeenCalledOnce();
-      expect(mockInvoke).toHaveBeenCalledWith(TAURI_COMMANDS.GET_GEMINI_MODELS, {
-        apiKey,
-      });
+      expect(mockInvoke).toHaveBeenCalledWith(TAURI_COMMANDS.GET_GEMINI_MODELS);
       expect(result).toEqual(mockModels);
     });
 
-    it('should pass apiKey correctly to invoke', async () => {
-      const apiKey = 'another-test-key';
+    it('should not send an API key over IPC', async () => {
       mockInvoke.mockResolvedValueOnce([]);
 
-      await ModelService.getGeminiModels(apiKey);
+      await ModelService.getGeminiModels();
 
       const callArgs = mockInvoke.mock.calls[0];
-      expect(callArgs[1].apiKey).toBe(apiKey);
+      expect(callArgs[1]).toBeUndefined();
     });
 
-    it('should handle invoke errors for invalid apiKey', async () => {
+    it('should handle invoke errors when the backend has no valid key', async () => {
       const error = new Error('Invalid API key');
       mockInvoke.mockRejectedValueOnce(error);
 
       await expect(
-        ModelService.getGeminiModels('invalid-key')
+        ModelService.getGeminiModels()
       ).rejects.toThrow('Invalid API key');
     });
   });
 
   describe('getGeminiModelsSorted', () => {
-    it('should call invoke with GET_GEMINI_MODELS_SORTED command and apiKey', async () => {
-      const apiKey = 'test-api-key-456';
+    it('should call invoke with GET_GEMINI_MODELS_SORTED command', async () => {
       const mockModels = ['gemini-1.5-pro', 'gemini-2.0-flash-exp'];
       mockInvoke.mockResolvedValueOnce(mockModels);
 
-      const result = await ModelService.getGeminiModelsSorted(apiKey);
+      const result = await ModelService.getGeminiModelsSorted();
 
       expect(mockInvoke).toHaveBeenCalledOnce();
       expect(mockInvoke).toHaveBeenCalledWith(
-        TAURI_COMMANDS.GET_GEMINI_MODELS_SORTED,
-        { apiKey }
+        TAURI_COMMANDS.GET_GEMINI_MODELS_SORTED
       );
       expect(result).toEqual(mockModels);
     });
@@ -207,7 +201,7 @@ describe('ModelService', () => {
       const mockModels = ['gemini-1.5-pro', 'gemini-1.5-flash', 'gemini-2.0-flash-exp'];
       mockInvoke.mockResolvedValueOnce(mockModels);
 
-      const result = await ModelService.getGeminiModelsSorted('api-key');
+      const result = await ModelService.getGeminiModelsSorted();
 
       expect(result).toHaveLength(3);
       expect(result).toEqual(mockModels);
@@ -276,9 +270,24 @@ describe('PromptService', () => {
         model,
         prompt,
         systemPrompt,
+        requestId: expect.any(String),
       });
     });
 
+    it('should pass and return the caller-chosen requestId', async () => {
+      mockInvoke.mockResolvedValueOnce(undefined);
+
+      const id = await PromptService.promptOllamaStream(
+        'llama3.2:1b',
+        'Stream test',
+        undefined,
+        'req-1'
+      );
+
+      expect(id).toBe('req-1');
+      expect(mockInvoke.mock.calls[0][1].requestId).toBe('req-1');
+    });
+
     it('should work without systemPrompt', async () => {
       mockInvoke.mockResolvedValueOnce(undefined);
 
@@ -288,6 +297,7 @@ describe('PromptService', () => {
         model: 'llama3.2:1b',
         prompt: 'Stream test',
         systemPrompt: undefined,
+        requestId: expect.any(String),
       });
     });
   });
@@ -296,7 +306,6 @@ describe('PromptService', () => {
     it('should call invoke with PROMPT_GEMINI_STREAM command and all parameters', async () => {
       const model = 'gemini-1.5-pro';
       const prompt = 'Test prompt';
-      const apiKey = 'test-key-789';
       const systemPrompt = 'You are Jaskier';
       const imageBase64 = 'base64-encoded-image';
       mockInvoke.mockResolvedValueOnce(undefined);
@@ -304,7 +313,6 @@ describe('PromptService', () => {
       await PromptService.promptGeminiStream(
         model,
         prompt,
-        apiKey,
         systemPrompt,
         imageBase64
       );
@@ -315,25 +323,23 @@ describe('PromptService', () => {
         {
           model,
           prompt,
-          apiKey,
           systemPrompt,
           imageBase64,
+          requestId: expect.any(String),
         }
       );
     });
 
-    it('should pass apiKey correctly to invoke', async () => {
-      const apiKey = 'critical-api-key';
+    it('should not send an API key over IPC', async () => {
       mockInvoke.mockResolvedValueOnce(undefined);
 
       await PromptService.promptGeminiStream(
         'gemini-2.0-flash-exp',
-        'prompt',
-        apiKey
+        'prompt'
       );
 
       const callArgs = mockInvoke.mock.calls[0];
-      expect(callArgs[1].apiKey).toBe(apiKey);
+      expect(callArgs[1]).not.toHaveProperty('apiKey');
     });
 
     it('should handle optional systemPrompt and imageBase64', async () => {
@@ -341,8 +347,7 @@ describe('PromptService', () => {
 
       await PromptService.promptGeminiStream(
         'gemini-1.5-flash',
-        'Simple prompt',
-        'api-key'
+        'Simple prompt'
       );
 
       const callArgs = mockInvoke.mock.calls[0];
@@ -350,15 +355,14 @@ describe('PromptService', () => {
       expect(callArgs[1].imageBase64).toBeUndefined();
     });
 
-    it('should handle invoke errors for invalid API key', async () => {
+    it('should handle invoke errors when the backend key is rejected', async () => {
       const error = new Error('Unauthorized');
       mockInvoke.mockRejectedValueOnce(error);
 
       await expect(
         PromptService.promptGeminiStream(
           'gemini-1.5-pro',
-          'prompt',
-          'invalid-key'
+          'prompt'
         )
       ).rejects.toThrow('Unauthorized');
     });
@@ -14,9 +14,8 @@ import type { BridgeState } from '../types';
 // TYPES
 // ============================================================================
 
+/** Non-secret .env entries; keys and tokens never leave the backend */
 export interface EnvVars {
-  GEMINI_API_KEY?: string;
-  GOOGLE_API_KEY?: string;
   OLLAMA_HOST?: string;
   [key: string]: string | undefined;
 }
@@ -96,15 +95,15 @@ export const ModelService = {
   /**
    * Get available Gemini models
    */
-  async getGeminiModels(apiKey: string): Promise<string[]> {
-    return invoke<string[]>(TAURI_COMMANDS.GET_GEMINI_MODELS, { apiKey });
+  async getGeminiModels(): Promise<string[]> {
+    return invoke<string[]>(TAURI_COMMANDS.GET_GEMINI_MODELS);
   },
 
   /**
    * Get Gemini models sorted by capability
    */
-  async getGeminiModelsSorted(apiKey: string): Promise<string[]> {
-    return invoke<string[]>(TAURI_COMMANDS.GET_GEMINI_MODELS_SORTED, { apiKey });
+  async getGeminiModelsSorted(): Promise<string[]> {
+    return invoke<string[]>(TAURI_COMMANDS.GET_GEMINI_MODELS_SORTED);
   },
 };
 
@@ -129,37 +128,43 @@ export const PromptService = {
   },
 
   /**
-   * Start Ollama streaming prompt
+   * Start Ollama streaming prompt.
+   * Resolves with the request ID that tags every payload of this stream.
    */
   async promptOllamaStream(
     model: string,
     prompt: string,
-    systemPrompt?: string
-  ): Promise<void> {
-    return invoke(TAURI_COMMANDS.PROMPT_OLLAMA_STREAM, {
+    systemPrompt?: string,
+    requestId: string = crypto.randomUUID()
+  ): Promise<string> {
+    await invoke(TAURI_COMMANDS.PROMPT_OLLAMA_STREAM, {
       model,
       prompt,
       systemPrompt,
+      requestId,
     });
+    return requestId;
   },
 
   /**
-   * Start Gemini streaming prompt
+   * Start Gemini streaming prompt.
+   * Resolves with the request ID that tags every payload of this stream.
    */
   async promptGeminiStream(
     model: string,
     prompt: string,
-    apiKey: string,
     systemPrompt?: string,
-    imageBase64?: string
-  ): Promise<void> {
-    return invoke(TAURI_COMMANDS.PROMPT_GEMINI_STREAM, {
+    imageBase64?: string,
+    requestId: string = crypto.randomUUID()
+  ): Promise<string> {
+    await invoke(TAURI_COMMANDS.PROMPT_GEMINI_STREAM, {
       model,
       prompt,
-      apiKey,
       systemPrompt,
       imageBase64,
+      requestId,
     });
+    return requestId;
   },
 };
 
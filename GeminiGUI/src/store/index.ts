@@ -40,7 +40,6 @@ export {
   selectSystemPrompt,
   selectDefaultProvider,
   selectUseSwarm,
-  selectGeminiApiKey,
 } from './selectors';
 
 // ============================================================================
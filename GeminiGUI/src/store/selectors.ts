@@ -58,12 +58,12 @@ export const selectSettings = (state: AppState) => state.settings;
 
 /**
  * Check if Gemini API key is set
- * Optimized selector to avoid full settings subscription
+ * The key lives in the backend; this mirrors its `has_secret` answer
  * @param state - Current app state
- * @returns Boolean indicating if API key exists and is non-empty
+ * @returns Boolean indicating if the backend holds an API key
  */
 export const selectIsApiKeySet = (state: AppState): boolean => {
-  return Boolean(state.settings.geminiApiKey && state.settings.geminiApiKey.length > 0);
+  return state.hasGeminiKey;
 };
 
 /**
@@ -104,16 +104,6 @@ export const selectUseSwarm = (state: AppState): boolean => {
   return state.settings.useSwarm;
 };
 
-/**
- * Get Gemini API key
- * Warning: Use with caution - returns sensitive data
- * @param state - Current app state
- * @returns API key string
- */
-export const selectGeminiApiKey = (state: AppState): string => {
-  return state.settings.geminiApiKey;
-};
-
 // ============================================================================
 // SESSION SELECTORS
 // ============================================================================
@@ -7,11 +7,11 @@
 
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import { invoke } from '@tauri-apps/api/core';
 
 import type { Message, Session, Settings, AppState } from '../types';
 import {
   isValidUrl,
-  isValidApiKey,
   sanitizeContent,
   sanitizeTitle,
 } from '../utils/validators';
@@ -19,6 +19,7 @@ import {
   LIMITS,
   DEFAULT_SETTINGS,
   STORAGE_KEYS,
+  TAURI_COMMANDS,
 } from '../constants';
 
 // ============================================================================
@@ -35,6 +36,7 @@ export const useAppStore = create<AppState>()(
       currentSessionId: null,
       chatHistory: {},
       settings: DEFAULT_SETTINGS,
+      hasGeminiKey: false,
       provider: 'ollama',
 
       setProvider: (provider) => set({ provider }),
@@ -234,15 +236,6 @@ export const useAppStore = create<AppState>()(
             }
           }
 
-          // Validate geminiApiKey
-          if (newSettings.geminiApiKey !== undefined) {
-            if (isValidApiKey(newSettings.geminiApiKey)) {
-              validated.geminiApiKey = newSettings.geminiApiKey;
-            } else {
-              console.warn('[Store] Invalid Gemini API key format');
-            }
-          }
-
           // Validate systemPrompt
           if (newSettings.systemPrompt !== undefined) {
             validated.systemPrompt = sanitizeContent(
@@ -260,9 +253,27 @@ export const useAppStore = create<AppState>()(
             settings: { ...state.settings, ...validated },
           };
         }),
+
+      setHasGeminiKey: (hasKey) => set({ hasGeminiKey: hasKey }),
     }),
     {
       name: STORAGE_KEYS.APP_STATE,
+      // v1: the Gemini key moved to the backend secret store
+      version: 1,
+      migrate: (persisted, version) => {
+        const state = persisted as Partial<AppState> & {
+          settings?: Settings & { geminiApiKey?: string };
+        };
+        if (version < 1 && state.settings) {
+          const { geminiApiKey, ...settings } = state.settings;
+          if (geminiApiKey) {
+            invoke(TAURI_COMMANDS.SET_SECRET, { name: 'GEMINI_API_KEY', value: geminiApiKey })
+              .catch((e) => console.warn('[Store] Failed to move Gemini API key to the backend:', e));
+          }
+          state.settings = settings;
+        }
+        return state as AppState;
+      },
       partialize: (state) => ({
         count: state.count,
         theme: state.theme,
@@ -298,11 +309,9 @@ export const selectCurrentMessages = (state: AppState): Message[] => {
 /**
  * Check if Gemini API key is set
  * @param state - Current app state
- * @returns Boolean indicating if API key exists and is non-empty
+ * @returns Last answer of the backend's `has_secret` check
  */
-export const selectIsApiKeySet = (state: AppState): boolean => {
-  return Boolean(state.settings.geminiApiKey && state.settings.geminiApiKey.length > 0);
-};
+export const selectIsApiKeySet = (state: AppState): boolean => state.hasGeminiKey;
 
 /**
  * Get session by ID (curried selector for memoization)
@@ -34,7 +34,6 @@ export type Provider = 'ollama' | 'gemini';
 export interface Settings {
   ollamaEndpoint: string;
   systemPrompt: string;
-  geminiApiKey: string;
   defaultProvider: Provider;
   useSwarm: boolean;
 }
@@ -52,6 +51,11 @@ export type Theme = 'dark' | 'light';
 export interface StreamPayload {
   chunk: string;
   done: boolean;
+  /** ID passed when the stream was started; absent on swarm events */
+  request_id?: string;
+  seq?: number;
+  cancelled?: boolean;
+  error?: string | null;
 }
 
 // ============================================================================
@@ -152,6 +156,8 @@ export interface AppState {
 
   // Settings
   settings: Settings;
+  /** Whether the backend holds a Gemini key; the key itself stays there */
+  hasGeminiKey: boolean;
 
   // Actions - Counter
   increment: () => void;
@@ -177,4 +183,5 @@ export interface AppState {
 
   // Actions - Settings
   updateSettings: (settings: Partial<Settings>) => void;
+  setHasGeminiKey: (hasKey: boolean) => void;
 }
@@ -11,9 +11,84 @@ function Get-BridgeData {
     return @{ requests = @(); auto_approve = $false }
 }
 
-function Set-BridgeData {
-    param($Data)
-    $Data | ConvertTo-Json -Depth 10 | Set-Content $BridgeFile
+# Same protocol as the GUI backend: hold bridge.json.lock while writing,
+# write a temp file and rename it over bridge.json, bump "revision".
+$LockFile = "$BridgeFile.lock"
+
+# Removes the lock if $IsExpected accepts it. It is first renamed to a name
+# only this call knows, so of two writers releasing the same lock only one
+# gets it, and a lock that replaced it in between is put back, not deleted.
+function Remove-BridgeLock {
+    param([scriptblock]$IsExpected)
+    $claimed = "$LockFile.claimed-$PID-$([guid]::NewGuid().ToString('N'))"
+    try {
+        Move-Item $LockFile $claimed -ErrorAction Stop
+    } catch {
+        return
+    }
+    if (-not (& $IsExpected $claimed)) {
+        Move-Item $claimed $LockFile -ErrorAction SilentlyContinue
+    }
+    Remove-Item $claimed -ErrorAction SilentlyContinue
+}
+
+function Enter-BridgeLock {
+    $token = "$PID $([guid]::NewGuid().ToString('N'))"
+    for ($i = 0; $i -lt 100; $i++) {
+        try {
+            $stream = [System.IO.File]::Open($LockFile, 'CreateNew', 'Write', 'None')
+        } catch {
+            # A lock older than 10 seconds was left behind by a crashed writer
+            $lock = Get-Item $LockFile -ErrorAction SilentlyContinue
+            if ($lock -and $lock.LastWriteTime -lt (Get-Date).AddSeconds(-10)) {
+                Remove-BridgeLock { param($path) (Get-Item $path).LastWriteTime -lt (Get-Date).AddSeconds(-10) }
+            }
+            Start-Sleep -Milliseconds 20
+            continue
+        }
+        $bytes = [System.Text.Encoding]::ASCII.GetBytes($token)
+        $stream.Write($bytes, 0, $bytes.Length)
+        $stream.Flush()
+        return @{ Stream = $stream; Token = $token }
+    }
+    throw "bridge.json is locked by another writer"
+}
+
+# A writer that stalled past 10 seconds may have had its lock broken and
+# taken by another; that one is left alone
+function Exit-BridgeLock {
+    param($Lock)
+    $Lock.Stream.Dispose()
+    Remove-BridgeLock { param($path) (Get-Content $path -Raw) -eq $Lock.Token }
+}
+
+function Update-BridgeData {
+    param([scriptblock]$Change)
+    $lock = Enter-BridgeLock
+    try {
+        $Data = Get-BridgeData
+        & $Change $Data
+        $revision = if ($Data.revision) { [int64]$Data.revision + 1 } else { 1 }
+        if ($Data -is [hashtable]) {
+            $Data.revision = $revision
+        } else {
+            $Data | Add-Member -NotePropertyName revision -NotePropertyValue $revision -Force
+        }
+        $tmp = "$BridgeFile.tmp"
+        $Data | ConvertTo-Json -Depth 10 | Set-Content $tmp
+        # Replacing fails on Windows while the GUI is reading the file
+        for ($i = 0; ; $i++) {
+            try {
+                Move-Item $tmp $BridgeFile -Force -ErrorAction Stop
+                break
+            } catch {
+                if ($i -ge 50) { throw }
+                Start-Sleep -Milliseconds 20
+            }
+        }
+    } finally {
+        Exit-BridgeLock $lock
+    }
 }
 
 # 1. Check Auto-Approve
@@ -31,9 +106,12 @@ $req = @{
     status = "pending"
 }
 
-if (-not $data.requests) { $data.requests = @() }
-$data.requests += $req
-Set-BridgeData $data
+# Appended under the lock to the current file, so a concurrent approval is not lost
+Update-BridgeData {
+    param($Data)
+    if (-not $Data.requests) { $Data.requests = @() }
+    $Data.requests += $req
+}
 
 Write-Host "Waiting for approval in GUI (ID: $id)..." -ForegroundColor Cyan
 